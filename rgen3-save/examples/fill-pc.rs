@@ -1,5 +1,5 @@
 use rand::{Rng as _, seq::IndexedRandom};
-use rgen3_save::{Pokemon, SaveSectionsMut, TrainerInfo};
+use rgen3_save::{Move, MoveTable, Pokemon, SaveSectionsMut, TrainerInfo};
 use std::collections::HashSet;
 
 static PREFIX_NAMES: [&str; 52] = [
@@ -21,16 +21,18 @@ struct PokeGen<'a> {
     chosen_species: HashSet<u16>,
     n_unique: u16,
     trainer: &'a TrainerInfo,
+    moves: &'a MoveTable,
     rng: rand::rngs::ThreadRng,
 }
 
 impl<'a> PokeGen<'a> {
-    fn new(trainer: &'a TrainerInfo) -> Self {
+    fn new(trainer: &'a TrainerInfo, moves: &'a MoveTable) -> Self {
         PokeGen {
             chosen_names: HashSet::new(),
             chosen_species: HashSet::new(),
             n_unique: 0,
             trainer,
+            moves,
             rng: rand::rng(),
         }
     }
@@ -66,15 +68,15 @@ impl<'a> PokeGen<'a> {
             }
             pokemon.data.growth.experience = 1_640_000;
             pokemon.data.growth.friendship = 0xFF;
+            // Four different moves, out of the 354 that exist, with their PP filled
+            let moves = rand::seq::index::sample(&mut self.rng, 354, 4);
+            for (slot, i) in moves.iter().enumerate() {
+                let move_ = Move::new(i as u16 + 1).unwrap();
+                pokemon
+                    .set_move(slot, Some(move_), self.moves)
+                    .expect("Missing base PP of a move");
+            }
             pokemon.data.growth.pp_bonuses = 0xFF;
-            pokemon.data.attacks.move1 = self.rng.random_range(0..354);
-            pokemon.data.attacks.move2 = self.rng.random_range(0..354);
-            pokemon.data.attacks.move3 = self.rng.random_range(0..354);
-            pokemon.data.attacks.move4 = self.rng.random_range(0..354);
-            pokemon.data.attacks.pp1 = 99;
-            pokemon.data.attacks.pp2 = 99;
-            pokemon.data.attacks.pp3 = 99;
-            pokemon.data.attacks.pp4 = 99;
             pokemon.data.evs_and_condition.hp = 0xFF;
             pokemon.data.evs_and_condition.attack = 0xFF;
             pokemon.data.evs_and_condition.defense = 0xFF;
@@ -96,12 +98,24 @@ impl<'a> PokeGen<'a> {
 fn main() {
     let mut args = std::env::args().skip(1);
     let path = args.next().expect("Need path to save as first arg");
+    // The base PP of the moves can be given as second arg, one per line. Without it, every
+    // move gets 99 PP.
+    let moves = match args.next() {
+        Some(pp_path) => MoveTable::load_pp(pp_path).unwrap(),
+        None => {
+            let mut moves = MoveTable::default();
+            for id in 1..=354 {
+                moves.set_pp(Move::new(id).unwrap(), 99);
+            }
+            moves
+        }
+    };
     let mut save = rgen3_save::Save::load_from_file(&path).unwrap();
     {
         let SaveSectionsMut {
             trainer, pc_boxes, ..
         } = save.sections_mut();
-        let mut generator = PokeGen::new(trainer);
+        let mut generator = PokeGen::new(trainer, &moves);
         for b in pc_boxes.iter_mut() {
             for p in &mut b.slots {
                 *p = Some(generator.random());
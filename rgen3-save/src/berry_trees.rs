@@ -0,0 +1,265 @@
+//! The berry trees, Ruby/Sapphire/Emerald only.
+//!
+//! Trees are numbered by the game's own tree ids. Where a tree is is part of the map data
+//! of the game, not of the save: each map places its trees as object events that carry the
+//! tree id, and Ruby/Sapphire and Emerald don't place the same trees. This crate only reads
+//! saves, so it has no map/coordinates table, and only the id of a tree is known here.
+
+use crate::{GameType, Save, UnsupportedGame};
+use byteorder::{ByteOrder, LittleEndian as LE};
+
+pub const N_BERRY_TREES: usize = 128;
+const BERRY_TREE_SIZE: usize = 8;
+const ENIGMA_BERRY: u8 = 43;
+
+fn berry_trees_offset(game: GameType) -> Result<usize, UnsupportedGame> {
+    match game {
+        GameType::RubyOrSapphire => Ok(0x1608),
+        GameType::Emerald => Ok(0x169C),
+        GameType::FireredOrLeafgreen => Err(UnsupportedGame),
+    }
+}
+
+/// Hours each growth stage of `berry` takes, the berry being numbered from 1 for Cheri.
+pub fn berry_growth_hours(berry: u8) -> Option<u16> {
+    match berry {
+        // Cheri, Chesto, Pecha, Rawst, Aspear
+        1..=5 => Some(3),
+        // Leppa, Oran, Persim
+        6..=8 => Some(4),
+        // Lum
+        9 => Some(12),
+        // Sitrus
+        10 => Some(8),
+        // Figy, Wiki, Mago, Aguav, Iapapa
+        11..=15 => Some(5),
+        // Razz, Bluk, Nanab, Wepear, Pinap
+        16..=20 => Some(2),
+        // Pomeg, Kelpsy, Qualot, Hondew, Grepa
+        21..=25 => Some(3),
+        // Tamato, Cornn, Magost, Rabuta, Nomel
+        26..=30 => Some(6),
+        // Spelon, Pamtre, Watmel, Durin, Belue
+        31..=35 => Some(15),
+        // The rare berries and the Enigma Berry
+        36..=ENIGMA_BERRY => Some(24),
+        _ => None,
+    }
+}
+
+/// The item of `berry`, the berry being numbered from 1 for Cheri.
+pub fn berry_item(berry: u8) -> u16 {
+    132 + u16::from(berry)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BerryStage {
+    /// Nothing is planted.
+    NoBerry,
+    Planted,
+    Sprouted,
+    Taller,
+    Flowering,
+    /// The berries can be picked.
+    Berries,
+    /// Just planted, and still sparkling.
+    Sparkling,
+}
+
+/// The stage of a sparkling tree. It is 255 in the game, but the stage is only 7 bits.
+const SPARKLING_STAGE: u8 = 0x7F;
+
+impl BerryStage {
+    fn from_u8(value: u8) -> Option<Self> {
+        use BerryStage::*;
+        match value {
+            0..=5 => {
+                Some([NoBerry, Planted, Sprouted, Taller, Flowering, Berries][usize::from(value)])
+            }
+            SPARKLING_STAGE => Some(Sparkling),
+            _ => None,
+        }
+    }
+    fn to_u8(self) -> u8 {
+        match self {
+            BerryStage::Sparkling => SPARKLING_STAGE,
+            stage => stage as u8,
+        }
+    }
+}
+
+/// A berry tree.
+#[derive(Clone, Copy, Debug)]
+pub struct BerryTree {
+    raw: [u8; BERRY_TREE_SIZE],
+}
+
+impl BerryTree {
+    /// The berry planted, numbered from 1 for Cheri, see `berry_item`.
+    pub fn berry(&self) -> u8 {
+        self.raw[0]
+    }
+    /// `None` if the stage is not a valid one.
+    pub fn stage(&self) -> Option<BerryStage> {
+        BerryStage::from_u8(self.raw[1] & 0x7F)
+    }
+    /// Whether the tree doesn't grow, like the ones only reachable later in the game.
+    pub fn growth_stopped(&self) -> bool {
+        self.raw[1] & 0x80 != 0
+    }
+    pub fn minutes_until_next_stage(&self) -> u16 {
+        LE::read_u16(&self.raw[2..])
+    }
+    /// How many berries can be picked.
+    pub fn berry_yield(&self) -> u8 {
+        self.raw[4]
+    }
+    pub fn set_berry_yield(&mut self, amount: u8) {
+        self.raw[4] = amount;
+    }
+    /// How many times the tree gave berries.
+    pub fn regrowth_count(&self) -> u8 {
+        self.raw[5] & 0xF
+    }
+    /// Whether the tree was watered during each of the four stages before the berries.
+    pub fn watered(&self) -> [bool; 4] {
+        let mut watered = [false; 4];
+        for (i, watered) in watered.iter_mut().enumerate() {
+            *watered = self.raw[5] & (0x10 << i) != 0;
+        }
+        watered
+    }
+    /// Make the tree ready to be picked right away. The yield is kept, but is at least 1 so
+    /// there is something to pick.
+    ///
+    /// Does nothing if nothing is planted.
+    pub fn grow(&mut self) {
+        let hours = match berry_growth_hours(self.berry()) {
+            Some(hours) => hours,
+            None => return,
+        };
+        if self.stage() == Some(BerryStage::NoBerry) {
+            return;
+        }
+        self.raw[1] = (self.raw[1] & 0x80) | BerryStage::Berries.to_u8();
+        // The berries stay four times longer than the other stages
+        LE::write_u16(&mut self.raw[2..], hours * 60 * 4);
+        self.raw[4] = self.raw[4].max(1);
+    }
+    /// Plant `berry` in the tree, replacing what was planted.
+    ///
+    /// Returns `false` and does nothing if `berry` is not a valid berry.
+    pub fn plant(&mut self, berry: u8) -> bool {
+        let hours = match berry_growth_hours(berry) {
+            Some(hours) => hours,
+            None => return false,
+        };
+        self.raw = [0; BERRY_TREE_SIZE];
+        self.raw[0] = berry;
+        self.raw[1] = BerryStage::Planted.to_u8();
+        LE::write_u16(&mut self.raw[2..], hours * 60);
+        true
+    }
+    /// Remove what is planted.
+    pub fn clear(&mut self) {
+        self.raw = [0; BERRY_TREE_SIZE];
+    }
+}
+
+/// A copy of the berry trees of a save.
+///
+/// Get it with `Save::berry_trees`, and write it back with `Save::set_berry_trees`.
+#[derive(Clone, Debug)]
+pub struct BerryTrees {
+    /// Every tree, by tree id.
+    pub trees: Vec<BerryTree>,
+}
+
+impl BerryTrees {
+    /// The trees where something is planted, with their ids.
+    pub fn list(&self) -> Vec<(usize, &BerryTree)> {
+        self.trees
+            .iter()
+            .enumerate()
+            .filter(|(_, tree)| tree.berry() != 0)
+            .collect()
+    }
+    /// Make every planted tree ready to be picked, see `BerryTree::grow`.
+    pub fn grow_all(&mut self) {
+        for tree in &mut self.trees {
+            tree.grow();
+        }
+    }
+}
+
+impl Save {
+    pub fn berry_trees(&self) -> Result<BerryTrees, UnsupportedGame> {
+        let offset = berry_trees_offset(self.game_type())?;
+        let mut raw = [0; N_BERRY_TREES * BERRY_TREE_SIZE];
+        self.read_game_state(offset, &mut raw);
+        let trees = raw
+            .chunks(BERRY_TREE_SIZE)
+            .map(|data| {
+                let mut tree = BerryTree {
+                    raw: [0; BERRY_TREE_SIZE],
+                };
+                tree.raw.copy_from_slice(data);
+                tree
+            })
+            .collect();
+        Ok(BerryTrees { trees })
+    }
+    /// # Panics
+    ///
+    /// Panics if `trees` doesn't have `N_BERRY_TREES` trees.
+    pub fn set_berry_trees(&mut self, trees: &BerryTrees) -> Result<(), UnsupportedGame> {
+        assert_eq!(
+            trees.trees.len(),
+            N_BERRY_TREES,
+            "Wrong amount of berry trees"
+        );
+        let offset = berry_trees_offset(self.game_type())?;
+        let mut raw = [0; N_BERRY_TREES * BERRY_TREE_SIZE];
+        for (tree, data) in trees.trees.iter().zip(raw.chunks_mut(BERRY_TREE_SIZE)) {
+            data.copy_from_slice(&tree.raw);
+        }
+        self.write_game_state(offset, &raw);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_berry_stage_encoding() {
+    let mut tree = BerryTree {
+        raw: [0; BERRY_TREE_SIZE],
+    };
+    for stage in 0..=5 {
+        let stage = BerryStage::from_u8(stage).unwrap();
+        assert_eq!(BerryStage::from_u8(stage.to_u8()), Some(stage));
+    }
+    // The growth stopped flag shares the byte of the stage
+    tree.raw[1] = 0x80 | BerryStage::Sparkling.to_u8();
+    assert_eq!(tree.stage(), Some(BerryStage::Sparkling));
+    assert!(tree.growth_stopped());
+    tree.raw[1] = 6;
+    assert_eq!(tree.stage(), None);
+}
+
+#[test]
+fn test_berry_tree_grow() {
+    let mut tree = BerryTree {
+        raw: [0; BERRY_TREE_SIZE],
+    };
+    tree.grow();
+    assert_eq!(tree.stage(), Some(BerryStage::NoBerry));
+    assert!(!tree.plant(44));
+    assert!(tree.plant(10));
+    assert_eq!(tree.stage(), Some(BerryStage::Planted));
+    assert_eq!(tree.minutes_until_next_stage(), 8 * 60);
+    tree.grow();
+    assert_eq!(tree.stage(), Some(BerryStage::Berries));
+    assert_eq!(tree.minutes_until_next_stage(), 8 * 60 * 4);
+    assert_eq!(tree.berry_yield(), 1);
+    tree.clear();
+    assert_eq!(tree.berry(), 0);
+}
@@ -0,0 +1,325 @@
+//! The Day Care, which holds up to two Pokémon and can produce eggs.
+
+use crate::{GameType, Pokemon, Save};
+use byteorder::{ByteOrder, LittleEndian as LE};
+use std::error::Error;
+
+const N_DAYCARE_SLOTS: usize = 2;
+const BOXED_POKEMON_LEN: usize = 80;
+/// Mail held by a Pokémon in the Day Care, along with the names it needs.
+const DAYCARE_MAIL_LEN: usize = 56;
+/// Script flag that makes the Day Care man show that an egg is waiting. Ruby/Sapphire/Emerald.
+const FLAG_PENDING_DAYCARE_EGG: u16 = 0x86;
+
+/// Where the parts of the Day Care are, relative to its start.
+///
+/// Ruby/Sapphire keeps the Pokémon, their mail and their steps in separate arrays, while the
+/// other games group each Pokémon with its mail and steps.
+struct Layout {
+    offset: usize,
+    len: usize,
+    egg_personality: usize,
+    step_counter: usize,
+}
+
+impl Layout {
+    fn new(game: GameType) -> Self {
+        match game {
+            GameType::RubyOrSapphire => Layout {
+                offset: 0x2F9C,
+                len: 0x11B,
+                egg_personality: 0x118,
+                step_counter: 0x11A,
+            },
+            GameType::Emerald | GameType::FireredOrLeafgreen => Layout {
+                offset: if game == GameType::Emerald {
+                    0x3030
+                } else {
+                    0x2F80
+                },
+                len: 0x11D,
+                egg_personality: 0x118,
+                step_counter: 0x11C,
+            },
+        }
+    }
+    /// Offsets of the Pokémon, its mail and its steps for slot `i`.
+    fn slot(game: GameType, i: usize) -> (usize, usize, usize) {
+        match game {
+            GameType::RubyOrSapphire => (
+                i * BOXED_POKEMON_LEN,
+                0xA0 + i * DAYCARE_MAIL_LEN,
+                0x110 + i * 4,
+            ),
+            GameType::Emerald | GameType::FireredOrLeafgreen => {
+                let start = i * 0x8C;
+                (start, start + BOXED_POKEMON_LEN, start + 0x88)
+            }
+        }
+    }
+}
+
+/// A copy of the Day Care of a save.
+///
+/// Get it with `Save::day_care`, and write it back with `Save::set_day_care`.
+#[derive(Debug)]
+pub struct DayCare {
+    pokemon: [Option<Pokemon>; N_DAYCARE_SLOTS],
+    raw: Vec<u8>,
+    game: GameType,
+}
+
+impl DayCare {
+    /// The Pokémon in `slot`, 0 or 1.
+    pub fn pokemon(&self, slot: usize) -> Option<&Pokemon> {
+        self.pokemon.get(slot).and_then(Option::as_ref)
+    }
+    pub fn pokemon_mut(&mut self, slot: usize) -> Option<&mut Pokemon> {
+        self.pokemon.get_mut(slot).and_then(Option::as_mut)
+    }
+    /// Leave a Pokémon at the Day Care, in the first free slot.
+    ///
+    /// The Pokémon must not have active data, like a PC Pokémon. Mail it holds is not kept.
+    /// Returns the slot it was put in, or gives it back if the Day Care is full.
+    pub fn insert(&mut self, pokemon: Pokemon) -> Result<usize, Pokemon> {
+        let slot = match self.pokemon.iter().position(Option::is_none) {
+            Some(slot) => slot,
+            None => return Err(pokemon),
+        };
+        self.pokemon[slot] = Some(pokemon);
+        self.set_steps(slot, 0);
+        self.clear_mail(slot);
+        Ok(slot)
+    }
+    /// Take the Pokémon in `slot` back.
+    ///
+    /// Like in the game, the other Pokémon moves to the first slot if needed, and the slot
+    /// left empty has its mail and steps cleared.
+    pub fn remove(&mut self, slot: usize) -> Option<Pokemon> {
+        let pokemon = self.pokemon.get_mut(slot)?.take()?;
+        let emptied = if slot == 0 && self.pokemon[1].is_some() {
+            self.pokemon.swap(0, 1);
+            let (_, mail_0, steps_0) = Layout::slot(self.game, 0);
+            let (_, mail_1, steps_1) = Layout::slot(self.game, 1);
+            self.raw
+                .copy_within(mail_1..mail_1 + DAYCARE_MAIL_LEN, mail_0);
+            self.raw.copy_within(steps_1..steps_1 + 4, steps_0);
+            1
+        } else {
+            slot
+        };
+        self.set_steps(emptied, 0);
+        self.clear_mail(emptied);
+        Some(pokemon)
+    }
+    /// Steps taken since the Pokémon in `slot` was left. It gains as much experience.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slot` is not 0 or 1.
+    pub fn steps(&self, slot: usize) -> u32 {
+        assert!(slot < N_DAYCARE_SLOTS, "Invalid Day Care slot: {}", slot);
+        let (_, _, steps) = Layout::slot(self.game, slot);
+        LE::read_u32(&self.raw[steps..])
+    }
+    /// # Panics
+    ///
+    /// Panics if `slot` is not 0 or 1.
+    pub fn set_steps(&mut self, slot: usize, steps: u32) {
+        assert!(slot < N_DAYCARE_SLOTS, "Invalid Day Care slot: {}", slot);
+        let (_, _, offset) = Layout::slot(self.game, slot);
+        LE::write_u32(&mut self.raw[offset..], steps);
+    }
+    /// Counts up to 255 steps, after which the game may produce an egg.
+    pub fn egg_step_counter(&self) -> u8 {
+        self.raw[Layout::new(self.game).step_counter]
+    }
+    pub fn set_egg_step_counter(&mut self, value: u8) {
+        self.raw[Layout::new(self.game).step_counter] = value;
+    }
+    /// Personality value of the egg waiting to be picked up, if there is one.
+    ///
+    /// Ruby/Sapphire only store the lower half; the rest is rolled when the egg is received.
+    pub fn pending_egg(&self) -> Option<u32> {
+        let offset = Layout::new(self.game).egg_personality;
+        let personality = match self.game {
+            GameType::RubyOrSapphire => u32::from(LE::read_u16(&self.raw[offset..])),
+            GameType::Emerald | GameType::FireredOrLeafgreen => LE::read_u32(&self.raw[offset..]),
+        };
+        if personality == 0 {
+            None
+        } else {
+            Some(personality)
+        }
+    }
+    /// Make an egg wait at the Day Care, with the given personality value.
+    ///
+    /// Ruby/Sapphire only keep the lower half, with the top bit set so it's never 0.
+    /// For the other games, a personality value of 0 clears the egg instead.
+    pub fn trigger_egg(&mut self, personality: u32) {
+        let offset = Layout::new(self.game).egg_personality;
+        match self.game {
+            GameType::RubyOrSapphire => {
+                LE::write_u16(&mut self.raw[offset..], personality as u16 | 0x8000)
+            }
+            GameType::Emerald | GameType::FireredOrLeafgreen => {
+                LE::write_u32(&mut self.raw[offset..], personality)
+            }
+        }
+    }
+    /// Remove the egg waiting at the Day Care.
+    pub fn clear_egg(&mut self) {
+        let offset = Layout::new(self.game).egg_personality;
+        match self.game {
+            GameType::RubyOrSapphire => LE::write_u16(&mut self.raw[offset..], 0),
+            GameType::Emerald | GameType::FireredOrLeafgreen => {
+                LE::write_u32(&mut self.raw[offset..], 0)
+            }
+        }
+    }
+    /// Reset the mail of `slot` the way the game does: no words, no names, no item.
+    fn clear_mail(&mut self, slot: usize) {
+        let (_, offset, _) = Layout::slot(self.game, slot);
+        let mail = &mut self.raw[offset..offset + DAYCARE_MAIL_LEN];
+        // Easy chat words
+        for b in &mut mail[..18] {
+            *b = 0xFF;
+        }
+        // Player name
+        for b in &mut mail[18..26] {
+            *b = 0xFF;
+        }
+        // Trainer ID, then species 1 and no item
+        mail[26..36].copy_from_slice(&[0, 0, 0, 0, 1, 0, 0, 0, 0, 0]);
+        // Original trainer and Pokémon names
+        for b in &mut mail[36..55] {
+            *b = 0;
+        }
+    }
+}
+
+impl Save {
+    /// Decode the Day Care.
+    ///
+    /// Fails if the data of a Pokémon in it can't be decoded.
+    pub fn day_care(&self) -> Result<DayCare, Box<dyn Error>> {
+        let game = self.game_type();
+        let layout = Layout::new(game);
+        let mut raw = vec![0; layout.len];
+        self.read_game_state(layout.offset, &mut raw);
+        let mut pokemon = [None, None];
+        for (i, slot) in pokemon.iter_mut().enumerate() {
+            let (offset, _, _) = Layout::slot(game, i);
+            let data = &raw[offset..offset + BOXED_POKEMON_LEN];
+            // If the entire data is zero bytes, then the slot is empty
+            if !data.iter().all(|&v| v == 0) {
+                *slot = Some(Pokemon::read_non_active(&mut &data[..])?);
+            }
+        }
+        Ok(DayCare { pokemon, raw, game })
+    }
+    /// Also sets the flag that makes the Day Care man show whether an egg is waiting.
+    ///
+    /// In Fire Red/Leaf Green, the number of that flag has not been checked against the
+    /// decompilation yet, so it is left as is: an egg triggered with `DayCare::trigger_egg`
+    /// is only offered once the game sets it, and a cleared one may still be offered.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `day_care` was not taken from a save of the same game.
+    pub fn set_day_care(&mut self, day_care: &DayCare) {
+        let game = self.game_type();
+        assert_eq!(day_care.game, game, "Day Care is from a different game");
+        let layout = Layout::new(game);
+        let mut raw = day_care.raw.clone();
+        for (i, slot) in day_care.pokemon.iter().enumerate() {
+            let (offset, _, _) = Layout::slot(game, i);
+            let mut writer = &mut raw[offset..offset + BOXED_POKEMON_LEN];
+            match *slot {
+                Some(ref pokemon) => pokemon
+                    .write_non_active(&mut writer)
+                    .expect("Boxed Pokémon data doesn't fit its slot"),
+                None => writer.fill(0),
+            }
+        }
+        self.write_game_state(layout.offset, &raw);
+        if game != GameType::FireredOrLeafgreen {
+            let mut flags = self.flags();
+            flags.set(FLAG_PENDING_DAYCARE_EGG, day_care.pending_egg().is_some());
+            self.set_flags(&flags);
+        }
+    }
+}
+
+#[test]
+fn test_remove_shifts_slots() {
+    for game in [GameType::RubyOrSapphire, GameType::Emerald] {
+        let mut day_care = DayCare {
+            pokemon: [None, None],
+            raw: vec![0; Layout::new(game).len],
+            game,
+        };
+        let second = Pokemon {
+            personality: 2,
+            ..Default::default()
+        };
+        assert_eq!(day_care.insert(Pokemon::default()).unwrap(), 0);
+        assert_eq!(day_care.insert(second).unwrap(), 1);
+        assert!(day_care.insert(Pokemon::default()).is_err());
+        day_care.set_steps(0, 100);
+        day_care.set_steps(1, 500);
+        assert_eq!(day_care.remove(0).unwrap().personality, 0);
+        assert_eq!(day_care.pokemon(0).unwrap().personality, 2);
+        assert!(day_care.pokemon(1).is_none());
+        assert_eq!(day_care.steps(0), 500);
+        assert_eq!(day_care.steps(1), 0);
+        assert_eq!(day_care.remove(0).unwrap().personality, 2);
+        assert_eq!(day_care.steps(0), 0);
+        assert!(day_care.remove(0).is_none());
+    }
+}
+
+#[test]
+fn test_day_care_roundtrip() {
+    use crate::GameVersion;
+    use std::io::Cursor;
+    for version in [
+        GameVersion::Ruby,
+        GameVersion::Emerald,
+        GameVersion::FireRed,
+    ] {
+        let mut save = crate::rw::blank_save(version);
+        let mut day_care = save.day_care().unwrap();
+        assert!(day_care.pokemon(0).is_none());
+        let pokemon = Pokemon {
+            personality: 0x1234_5678,
+            ot_id: 0x9ABC_DEF0,
+            ..Default::default()
+        };
+        day_care.insert(pokemon).unwrap();
+        day_care.set_steps(0, 1000);
+        day_care.set_egg_step_counter(7);
+        day_care.trigger_egg(0x4242);
+        save.set_day_care(&day_care);
+        let mut data = Vec::new();
+        save.write(&mut data).unwrap();
+        let save = Save::read_as(&mut Cursor::new(data), version).unwrap();
+        let day_care = save.day_care().unwrap();
+        let pokemon = day_care.pokemon(0).unwrap();
+        assert_eq!(pokemon.personality, 0x1234_5678);
+        assert_eq!(pokemon.ot_id, 0x9ABC_DEF0);
+        assert!(day_care.pokemon(1).is_none());
+        assert_eq!(day_care.steps(0), 1000);
+        assert_eq!(day_care.egg_step_counter(), 7);
+        // Ruby/Sapphire set the top bit of the half they keep
+        let egg = if version == GameVersion::Ruby {
+            0xC242
+        } else {
+            0x4242
+        };
+        assert_eq!(day_care.pending_egg(), Some(egg));
+        if version != GameVersion::FireRed {
+            assert!(save.flags().get(FLAG_PENDING_DAYCARE_EGG));
+        }
+    }
+}
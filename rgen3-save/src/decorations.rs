@@ -0,0 +1,190 @@
+//! The decorations the player owns, for their secret base. Ruby/Sapphire/Emerald only.
+
+use crate::secret_bases::SECRET_BASE_DECORATIONS;
+use crate::{GameType, Save, UnsupportedGame};
+
+/// Decorations placed in the player's bedroom.
+const N_ROOM_DECORATIONS: usize = 12;
+
+/// Offsets of the decorations placed in the player's own secret base, and in their bedroom.
+fn placed_offsets(game: GameType) -> Result<(usize, usize), UnsupportedGame> {
+    match game {
+        GameType::RubyOrSapphire => Ok((0x1A1A, 0x2688)),
+        GameType::Emerald => Ok((0x1AAE, 0x271C)),
+        GameType::FireredOrLeafgreen => Err(UnsupportedGame),
+    }
+}
+
+fn decorations_offset(game: GameType) -> Result<usize, UnsupportedGame> {
+    match game {
+        GameType::RubyOrSapphire => Ok(0x26A0),
+        GameType::Emerald => Ok(0x2734),
+        GameType::FireredOrLeafgreen => Err(UnsupportedGame),
+    }
+}
+
+/// The pockets of the decoration inventory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecorationCategory {
+    Desk,
+    Chair,
+    Plant,
+    Ornament,
+    Mat,
+    Poster,
+    Doll,
+    Cushion,
+}
+
+impl DecorationCategory {
+    /// The category of decoration `id`, if it is a valid decoration.
+    pub fn of(id: u8) -> Option<Self> {
+        use DecorationCategory::*;
+        // Decorations are numbered by category, except for the big dolls at the end
+        match id {
+            1..=9 => Some(Desk),
+            10..=18 => Some(Chair),
+            19..=24 => Some(Plant),
+            25..=47 => Some(Ornament),
+            48..=65 => Some(Mat),
+            66..=75 => Some(Poster),
+            76..=100 | 111..=120 => Some(Doll),
+            101..=110 => Some(Cushion),
+            _ => None,
+        }
+    }
+    pub const ALL: [DecorationCategory; 8] = [
+        DecorationCategory::Desk,
+        DecorationCategory::Chair,
+        DecorationCategory::Plant,
+        DecorationCategory::Ornament,
+        DecorationCategory::Mat,
+        DecorationCategory::Poster,
+        DecorationCategory::Doll,
+        DecorationCategory::Cushion,
+    ];
+    /// How many decorations of this category the player can own.
+    pub fn capacity(self) -> usize {
+        match self {
+            DecorationCategory::Ornament | DecorationCategory::Mat => 30,
+            DecorationCategory::Doll => 40,
+            _ => 10,
+        }
+    }
+    /// Where the slots of this category are in the inventory.
+    fn range(self) -> std::ops::Range<usize> {
+        let start = DecorationCategory::ALL
+            .iter()
+            .take_while(|&&c| c != self)
+            .map(|c| c.capacity())
+            .sum();
+        start..start + self.capacity()
+    }
+}
+
+/// Error returned when a decoration can't be added.
+#[derive(Debug)]
+pub struct CantAddDecoration;
+
+/// A copy of the decoration inventory of a save.
+///
+/// Get it with `Save::decorations`, and write it back with `Save::set_decorations`.
+#[derive(Clone, Debug)]
+pub struct Decorations {
+    slots: Vec<u8>,
+    /// The decorations placed in the player's secret base and bedroom.
+    placed: Vec<u8>,
+}
+
+impl Decorations {
+    /// The ids of the decorations owned in `category`. Decorations placed in the player's base
+    /// are still listed.
+    pub fn list(&self, category: DecorationCategory) -> Vec<u8> {
+        self.slots[category.range()]
+            .iter()
+            .cloned()
+            .filter(|&id| id != 0)
+            .collect()
+    }
+    /// Give the player decoration `id`.
+    ///
+    /// Fails if `id` is not a valid decoration, it is not of `category`, or the category is
+    /// full.
+    pub fn add(&mut self, category: DecorationCategory, id: u8) -> Result<(), CantAddDecoration> {
+        if DecorationCategory::of(id) != Some(category) {
+            return Err(CantAddDecoration);
+        }
+        let slot = self.slots[category.range()]
+            .iter_mut()
+            .find(|id| **id == 0)
+            .ok_or(CantAddDecoration)?;
+        *slot = id;
+        Ok(())
+    }
+    /// Take one decoration `id` away from the player. Returns whether one was taken: not if
+    /// they have none, or if all they have are placed in their secret base or bedroom, as the
+    /// game doesn't let them throw those away.
+    ///
+    /// Like in the game, the remaining decorations of the category are moved up.
+    pub fn remove(&mut self, category: DecorationCategory, id: u8) -> bool {
+        let placed = self.placed.iter().filter(|&&placed| placed == id).count();
+        let slots = &mut self.slots[category.range()];
+        let owned = slots.iter().filter(|&&slot| slot == id).count();
+        if id == 0 || owned <= placed {
+            return false;
+        }
+        let index = match slots.iter().position(|&slot| slot == id) {
+            Some(index) => index,
+            None => return false,
+        };
+        slots[index..].rotate_left(1);
+        *slots.last_mut().unwrap() = 0;
+        true
+    }
+}
+
+impl Save {
+    pub fn decorations(&self) -> Result<Decorations, UnsupportedGame> {
+        let offset = decorations_offset(self.game_type())?;
+        let len = DecorationCategory::ALL.iter().map(|c| c.capacity()).sum();
+        let mut slots = vec![0; len];
+        self.read_game_state(offset, &mut slots);
+        let (base_offset, room_offset) = placed_offsets(self.game_type())?;
+        let mut placed = vec![0; SECRET_BASE_DECORATIONS + N_ROOM_DECORATIONS];
+        let (base, room) = placed.split_at_mut(SECRET_BASE_DECORATIONS);
+        self.read_game_state(base_offset, base);
+        self.read_game_state(room_offset, room);
+        Ok(Decorations { slots, placed })
+    }
+    pub fn set_decorations(&mut self, decorations: &Decorations) -> Result<(), UnsupportedGame> {
+        let offset = decorations_offset(self.game_type())?;
+        self.write_game_state(offset, &decorations.slots);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_decorations_add_remove() {
+    let len = DecorationCategory::ALL.iter().map(|c| c.capacity()).sum();
+    let mut decorations = Decorations {
+        slots: vec![0; len],
+        placed: vec![0; SECRET_BASE_DECORATIONS + N_ROOM_DECORATIONS],
+    };
+    assert!(decorations.add(DecorationCategory::Desk, 1).is_ok());
+    assert!(decorations.add(DecorationCategory::Chair, 1).is_err());
+    assert!(decorations.add(DecorationCategory::Doll, 120).is_ok());
+    assert!(decorations.add(DecorationCategory::Doll, 121).is_err());
+    for _ in 0..9 {
+        decorations.add(DecorationCategory::Desk, 2).unwrap();
+    }
+    assert!(decorations.add(DecorationCategory::Desk, 3).is_err());
+    // One of the two Pokémon Desks is placed in the secret base
+    decorations.placed[0] = 2;
+    decorations.remove(DecorationCategory::Desk, 1);
+    assert_eq!(decorations.list(DecorationCategory::Desk), [2; 9]);
+    for _ in 0..8 {
+        assert!(decorations.remove(DecorationCategory::Desk, 2));
+    }
+    assert!(!decorations.remove(DecorationCategory::Desk, 2));
+    assert_eq!(decorations.list(DecorationCategory::Desk), [2]);
+}
@@ -0,0 +1,252 @@
+//! Easy chat words, which mail and various phrases are made of.
+//!
+//! A word is a group and an index in that group. The word lists are game data that is not
+//! part of the save. The groups of Pokémon and moves are indexed by species and move id, so
+//! they are built in, while the other groups have to be loaded by the user. They are, by
+//! group number: trainer (1), status (2), battle (3), greetings (4), people (5), voices (6),
+//! speech (7), endings (8), feelings (9), conditions (10), actions (11), lifestyle (12),
+//! hobbies (13), time (14), misc (15), adjectives (16), events (17) and trendy sayings (20).
+//!
+//! The phrases of the player's profile and the ones said in link battles are accessed through
+//! `Save::phrase`. The answers given in TV interviews are part of the TV shows, see
+//! `Save::tv_interviews`.
+
+use crate::moves::LAST_MOVE;
+use crate::{GameType, Move, Save};
+use byteorder::{ByteOrder, LittleEndian as LE};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Group of the Pokémon names, indexed by species.
+pub const EASY_CHAT_GROUP_POKEMON: u8 = 0;
+/// The two groups of move names, indexed by move id. Each holds part of the moves.
+pub const EASY_CHAT_GROUP_MOVE_1: u8 = 18;
+pub const EASY_CHAT_GROUP_MOVE_2: u8 = 19;
+/// Group of the Pokémon names that are unlocked with the National Dex, indexed by species.
+pub const EASY_CHAT_GROUP_POKEMON_NATIONAL: u8 = 21;
+
+/// Amount of words in a phrase.
+pub const PHRASE_WORDS: usize = 6;
+
+fn phrases_offset(game: GameType) -> usize {
+    match game {
+        GameType::RubyOrSapphire => 0x2B1C,
+        GameType::Emerald => 0x2BB0,
+        GameType::FireredOrLeafgreen => 0x2CA0,
+    }
+}
+
+/// A word of an easy chat phrase.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct EasyChatWord(pub u16);
+
+impl EasyChatWord {
+    /// No word.
+    pub const EMPTY: EasyChatWord = EasyChatWord(0xFFFF);
+    pub fn new(group: u8, index: u16) -> Self {
+        EasyChatWord((u16::from(group) << 9) | (index & 0x1FF))
+    }
+    pub fn group(self) -> u8 {
+        (self.0 >> 9) as u8
+    }
+    pub fn index(self) -> u16 {
+        self.0 & 0x1FF
+    }
+    pub fn is_empty(self) -> bool {
+        self == EasyChatWord::EMPTY
+    }
+}
+
+macro_rules! make_poke_map {
+    ($($id:literal,$name:literal,$($kind:literal,)+;)+) => {
+        vec![$(($id, $name),)+]
+    }
+}
+
+#[allow(clippy::zero_prefixed_literal)]
+fn pokemon_names() -> Vec<(u16, &'static str)> {
+    include!("../../poke.incl")
+}
+
+/// The English name of `species`, if it is a valid species.
+pub(crate) fn species_name(species: u16) -> Option<&'static str> {
+    pokemon_names()
+        .into_iter()
+        .find(|&(id, name)| id == species && id != 0 && !name.starts_with('?'))
+        .map(|(_, name)| name)
+}
+
+/// The text of the easy chat words, by group.
+#[derive(Clone, Debug)]
+pub struct WordTable {
+    groups: BTreeMap<u8, Vec<String>>,
+}
+
+impl Default for WordTable {
+    /// A table with the Pokémon and move names.
+    fn default() -> Self {
+        let mut species_names = Vec::new();
+        for (species, name) in pokemon_names() {
+            let species = usize::from(species);
+            if species_names.len() <= species {
+                species_names.resize(species + 1, String::new());
+            }
+            // Glitch species are not valid words
+            if species != 0 && !name.starts_with('?') {
+                species_names[species] = name.to_owned();
+            }
+        }
+        let move_names: Vec<String> = (0..=LAST_MOVE)
+            .map(|id| Move::new(id).map_or(String::new(), |move_| move_.name().to_owned()))
+            .collect();
+        let mut groups = BTreeMap::new();
+        groups.insert(EASY_CHAT_GROUP_POKEMON, species_names.clone());
+        groups.insert(EASY_CHAT_GROUP_POKEMON_NATIONAL, species_names);
+        groups.insert(EASY_CHAT_GROUP_MOVE_1, move_names.clone());
+        groups.insert(EASY_CHAT_GROUP_MOVE_2, move_names);
+        WordTable { groups }
+    }
+}
+
+impl WordTable {
+    /// Set the words of `group`, in order.
+    pub fn set_group(&mut self, group: u8, words: Vec<String>) {
+        self.groups.insert(group, words);
+    }
+    /// Load the words of `group` from a text file, with one word per line.
+    pub fn load_group<P: AsRef<Path>>(&mut self, group: u8, path: P) -> io::Result<()> {
+        let words = fs::read_to_string(path)?
+            .lines()
+            .map(|line| line.trim().to_owned())
+            .collect();
+        self.set_group(group, words);
+        Ok(())
+    }
+    /// The text of `word`, if its group is known.
+    pub fn word(&self, word: EasyChatWord) -> Option<&str> {
+        let text = self
+            .groups
+            .get(&word.group())?
+            .get(usize::from(word.index()))?;
+        if text.is_empty() { None } else { Some(text) }
+    }
+    /// Find the word with the text `text`, ignoring case.
+    ///
+    /// If several groups have the word, the one of the lowest group is given. The game only
+    /// accepts Pokémon and moves from one of their two groups, which the built-in groups don't
+    /// tell apart, so they may have to be moved to the other group.
+    pub fn find(&self, text: &str) -> Option<EasyChatWord> {
+        let text = text.to_lowercase();
+        self.groups.iter().find_map(|(&group, words)| {
+            let index = words.iter().position(|w| w.to_lowercase() == text)?;
+            Some(EasyChatWord::new(group, index as u16))
+        })
+    }
+    /// Turn `text` into words, splitting it on whitespace. Words that contain spaces can't be
+    /// found this way.
+    ///
+    /// Fails if a word is not in the table.
+    pub fn encode(&self, text: &str) -> Result<Vec<EasyChatWord>, InvalidPhrase> {
+        text.split_whitespace()
+            .map(|word| self.find(word).ok_or(InvalidPhrase))
+            .collect()
+    }
+    /// Turn `words` into text. Unknown words are shown as `[group:index]`.
+    pub fn decode(&self, words: &[EasyChatWord]) -> String {
+        let words: Vec<String> = words
+            .iter()
+            .filter(|w| !w.is_empty())
+            .map(|&w| match self.word(w) {
+                Some(text) => text.to_owned(),
+                None => format!("[{}:{}]", w.group(), w.index()),
+            })
+            .collect();
+        words.join(" ")
+    }
+}
+
+/// Error returned when some text can't be turned into a phrase, because it has an unknown word
+/// or too many words.
+#[derive(Debug)]
+pub struct InvalidPhrase;
+
+/// The phrases the player sets, in the order they are stored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PhraseKind {
+    /// The greeting of the player's profile.
+    Profile,
+    /// Said when a link battle starts.
+    BattleStart,
+    /// Said when the player wins a link battle.
+    BattleWon,
+    /// Said when the player loses a link battle.
+    BattleLost,
+}
+
+/// A phrase of up to `PHRASE_WORDS` words.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Phrase {
+    /// Unused words are `EasyChatWord::EMPTY`.
+    pub words: [EasyChatWord; PHRASE_WORDS],
+}
+
+impl Phrase {
+    /// Make a phrase out of `text`, see `WordTable::encode`.
+    ///
+    /// Fails if a word is unknown, or there are too many words.
+    pub fn from_text(table: &WordTable, text: &str) -> Result<Self, InvalidPhrase> {
+        let encoded = table.encode(text)?;
+        if encoded.len() > PHRASE_WORDS {
+            return Err(InvalidPhrase);
+        }
+        let mut words = [EasyChatWord::EMPTY; PHRASE_WORDS];
+        words[..encoded.len()].copy_from_slice(&encoded);
+        Ok(Phrase { words })
+    }
+    pub fn text(&self, table: &WordTable) -> String {
+        table.decode(&self.words)
+    }
+}
+
+impl Save {
+    pub fn phrase(&self, kind: PhraseKind) -> Phrase {
+        let offset = phrases_offset(self.game_type()) + kind as usize * PHRASE_WORDS * 2;
+        let mut raw = [0; PHRASE_WORDS * 2];
+        self.read_game_state(offset, &mut raw);
+        let mut words = [EasyChatWord::EMPTY; PHRASE_WORDS];
+        for (word, data) in words.iter_mut().zip(raw.chunks(2)) {
+            *word = EasyChatWord(LE::read_u16(data));
+        }
+        Phrase { words }
+    }
+    pub fn set_phrase(&mut self, kind: PhraseKind, phrase: &Phrase) {
+        let offset = phrases_offset(self.game_type()) + kind as usize * PHRASE_WORDS * 2;
+        let mut raw = [0; PHRASE_WORDS * 2];
+        for (word, data) in phrase.words.iter().zip(raw.chunks_mut(2)) {
+            LE::write_u16(data, word.0);
+        }
+        self.write_game_state(offset, &raw);
+    }
+}
+
+#[test]
+fn test_word_table() {
+    let table = WordTable::default();
+    let pikachu = EasyChatWord::new(EASY_CHAT_GROUP_POKEMON, 25);
+    let surf = EasyChatWord::new(EASY_CHAT_GROUP_MOVE_1, 57);
+    assert_eq!(table.find("pikachu"), Some(pikachu));
+    assert_eq!(
+        table.find("Tackle"),
+        Some(EasyChatWord::new(EASY_CHAT_GROUP_MOVE_1, 33))
+    );
+    assert_eq!(
+        table.decode(&[pikachu, EasyChatWord::EMPTY, surf, EasyChatWord::new(4, 0)]),
+        "Pikachu SURF [4:0]"
+    );
+    let phrase = Phrase::from_text(&table, "Pikachu tackle").unwrap();
+    assert_eq!(phrase.words[1].index(), 33);
+    assert!(phrase.words[2].is_empty());
+    assert!(Phrase::from_text(&table, "Pikachu nothing").is_err());
+}
@@ -0,0 +1,186 @@
+//! Eggs, and hatching them.
+
+use crate::easy_chat::species_name;
+use crate::species::SpeciesInfo;
+use crate::{
+    GameVersion, Language, Move, MoveTable, N_MOVE_SLOTS, Pokemon, PokemonNick, TrainerInfo,
+};
+use rgen3_string::Charset;
+
+/// Flags in the high byte of the language of a Pokémon.
+const HAS_SPECIES_FLAG: u16 = 0x200;
+const IS_EGG_FLAG: u16 = 0x400;
+const IVS_EGG_BIT: u32 = 1 << 30;
+/// The nickname of every egg, "タマゴ" in the Japanese charset.
+const EGG_NICKNAME: [u8; 3] = [0x60, 0x6F, 0x8B];
+const POKE_BALL: u16 = 4;
+const HATCHED_FRIENDSHIP: u8 = 120;
+/// The level eggs are made at, and hatch at.
+const EGG_LEVEL: u8 = 5;
+
+/// Error returned when hatching a Pokémon that is not an egg.
+#[derive(Debug)]
+pub struct NotAnEgg;
+
+/// Error returned when an egg can't be made.
+#[derive(Debug)]
+pub struct CantMakeEgg;
+
+impl Pokemon {
+    /// Make an egg of `species`, the way the Day Care man gives it.
+    ///
+    /// The egg counts down the egg cycles of the species in its friendship until it hatches.
+    /// `ivs` holds the IVs and the ability bit, packed the same way as the game stores them.
+    /// The egg gets `trainer` as original trainer, and `game` as game of origin, which should
+    /// be an exact version.
+    ///
+    /// Like in the game, the egg has the experience of level 5 for the growth rate in `info`,
+    /// and the last moves the species learns by leveling up to level 5 in `game`, with their
+    /// PP from `moves`. Moves inherited from the parents are not added. The egg has no active
+    /// data, so it can go in the PC as is.
+    ///
+    /// Fails if the species is not valid, `info` has no learnset for `game`, or the PP of one
+    /// of the moves is not in `moves`.
+    pub fn new_egg(
+        species: u16,
+        info: &SpeciesInfo,
+        moves: &MoveTable,
+        personality: u32,
+        ivs: u32,
+        trainer: &TrainerInfo,
+        game: GameVersion,
+    ) -> Result<Self, CantMakeEgg> {
+        let mut egg = Pokemon::default();
+        egg.set_species(species).map_err(|_| CantMakeEgg)?;
+        egg.personality = personality;
+        egg.ot_id = trainer.full_id();
+        egg.ot_name = trainer.name;
+        egg.nickname = PokemonNick([0xFF; 10]);
+        egg.nickname.0[..EGG_NICKNAME.len()].copy_from_slice(&EGG_NICKNAME);
+        // Eggs are always Japanese, whatever the game
+        egg.language = HAS_SPECIES_FLAG | IS_EGG_FLAG | Language::Japanese as u16;
+        egg.data.growth.experience = info.growth_rate.experience(EGG_LEVEL);
+        egg.data.growth.friendship = info.egg_cycles;
+        // Met at level 0, in a Poké Ball
+        egg.data.misc.origins_info = (u16::from(trainer.gender() as u8) << 15)
+            | (POKE_BALL << 11)
+            | (game.origin_game_code() << 7);
+        egg.data.misc.ivs_eggs_and_ability = (ivs & !IVS_EGG_BIT) | IVS_EGG_BIT;
+        // Each move learned pushes out the first one once all slots are taken
+        let learnset = info.learnsets.get(game).ok_or(CantMakeEgg)?;
+        let mut known: Vec<Move> = Vec::new();
+        for &(level, id) in &learnset.level_up {
+            let move_ = Move::new(id).map_err(|_| CantMakeEgg)?;
+            if level > EGG_LEVEL || known.contains(&move_) {
+                continue;
+            }
+            if known.len() == N_MOVE_SLOTS {
+                known.remove(0);
+            }
+            known.push(move_);
+        }
+        for (slot, move_) in known.into_iter().enumerate() {
+            egg.set_move(slot, Some(move_), moves)
+                .map_err(|_| CantMakeEgg)?;
+        }
+        Ok(egg)
+    }
+    pub fn is_egg(&self) -> bool {
+        self.data.misc.ivs_eggs_and_ability & IVS_EGG_BIT != 0
+    }
+    /// How many egg cycles are left before the egg hatches.
+    pub fn egg_cycles(&self) -> Option<u8> {
+        if self.is_egg() {
+            Some(self.data.growth.friendship)
+        } else {
+            None
+        }
+    }
+    /// Hatch the egg, like the game does: it's named after its species, gets the language of
+    /// the game it hatches in and the friendship of a newly hatched Pokémon, and is met at
+    /// `met_location`, at level 0.
+    ///
+    /// The name is the English species name. In other western languages, the nickname has to
+    /// be set to the species name of the language, or the Pokémon counts as nicknamed. In
+    /// Japanese, the nickname is left as the one of the egg, and has to be set.
+    pub fn hatch(&mut self, language: Language, met_location: u8) -> Result<(), NotAnEgg> {
+        if !self.is_egg() {
+            return Err(NotAnEgg);
+        }
+        self.data.misc.ivs_eggs_and_ability &= !IVS_EGG_BIT;
+        self.language = (self.language & !IS_EGG_FLAG & 0xFF00) | language as u16;
+        if let Some(name) = species_name(self.data.growth.species)
+            && language.charset() == Charset::Western
+        {
+            rgen3_string::encode_string(&name.to_uppercase(), &mut self.nickname.0);
+        }
+        self.data.growth.friendship = HATCHED_FRIENDSHIP;
+        self.data.misc.met_location = met_location;
+        self.data.misc.origins_info &= !0x7F;
+        Ok(())
+    }
+}
+
+#[test]
+fn test_egg_hatch() {
+    use crate::species::{GrowthRate, Learnset};
+    let save = crate::rw::blank_save(GameVersion::Emerald);
+    let trainer = save.sections().trainer;
+    let (emerald, ruby) = (GameVersion::Emerald, GameVersion::Ruby);
+    let ids = [84, 45, 39, 86, 98];
+    let mut moves = MoveTable::default();
+    for id in ids {
+        moves.set_pp(Move::new(id).unwrap(), 30);
+    }
+    let mut info = SpeciesInfo {
+        base_stats: [50; 6],
+        growth_rate: GrowthRate::MediumFast,
+        egg_cycles: 20,
+        evolutions: Vec::new(),
+        learnsets: Default::default(),
+    };
+    // Five moves by level 5, a repeated one, and one learned later
+    let level_up = vec![
+        (1, 84),
+        (1, 45),
+        (1, 84),
+        (2, 39),
+        (4, 86),
+        (5, 98),
+        (6, 104),
+    ];
+    let learnset = Learnset {
+        level_up,
+        ..Default::default()
+    };
+    info.learnsets.insert(emerald, learnset);
+    let egg = Pokemon::new_egg(25, &info, &moves, 0x1234, 0x3FFF_FFFF, trainer, emerald);
+    let egg = egg.unwrap();
+    assert!(Pokemon::new_egg(253, &info, &moves, 0, 0, trainer, emerald).is_err());
+    assert!(Pokemon::new_egg(25, &info, &moves, 0, 0, trainer, ruby).is_err());
+    assert!(egg.is_egg());
+    assert_eq!(egg.egg_cycles(), Some(20));
+    assert_eq!(egg.data.misc.origin_game(), 3);
+    assert_eq!(egg.data.growth.experience, 125);
+    let known: Vec<u16> = egg
+        .moves()
+        .iter()
+        .flatten()
+        .map(|move_| move_.id())
+        .collect();
+    assert_eq!(known, ids[1..]);
+    assert_eq!(egg.data.attacks.pp4, 30);
+    // The egg survives being written and read back
+    let mut raw = [0; 80];
+    egg.write_non_active(&mut &mut raw[..]).unwrap();
+    let mut egg = Pokemon::read_non_active(&mut &raw[..]).unwrap();
+    assert!(egg.is_egg());
+    egg.hatch(Language::English, 0x10).unwrap();
+    assert!(!egg.is_egg());
+    assert_eq!(egg.egg_cycles(), None);
+    assert_eq!(egg.nickname_text(), "PIKACHU");
+    assert_eq!(egg.language, HAS_SPECIES_FLAG | Language::English as u16);
+    assert_eq!(egg.data.growth.friendship, HATCHED_FRIENDSHIP);
+    assert_eq!(egg.data.misc.met_location, 0x10);
+    assert!(egg.hatch(Language::English, 0x10).is_err());
+}
@@ -0,0 +1,293 @@
+//! Evolving Pokémon.
+
+use crate::easy_chat::species_name;
+use crate::species::{EvolutionMethod, SpeciesInfo, SpeciesTable};
+use crate::{Pokemon, Save};
+use rgen3_string::Charset;
+
+const EVERSTONE: u16 = 195;
+const MIN_FRIENDSHIP_TO_EVOLVE: u8 = 220;
+const PARTY_SIZE: usize = 6;
+const MAIL_NONE: u8 = 0xFF;
+
+/// What makes a Pokémon evolve.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EvolutionTrigger {
+    /// Leveling up, during the day or the night.
+    LevelUp {
+        night: bool,
+    },
+    Trade,
+    /// Using an item on the Pokémon.
+    Item(u16),
+}
+
+/// Error returned when a Pokémon can't evolve.
+#[derive(Debug)]
+pub struct CantEvolve;
+
+/// The nature multiplier of a stat other than HP, in tenths.
+fn nature_multiplier(nature: u32, stat: u32) -> u32 {
+    let (raised, lowered) = (nature / 5, nature % 5);
+    if raised == lowered {
+        10
+    } else if stat == raised {
+        11
+    } else if stat == lowered {
+        9
+    } else {
+        10
+    }
+}
+
+impl Pokemon {
+    fn ivs(&self) -> [u32; 6] {
+        let mut ivs = [0; 6];
+        for (i, iv) in ivs.iter_mut().enumerate() {
+            *iv = (self.data.misc.ivs_eggs_and_ability >> (i * 5)) & 0x1F;
+        }
+        ivs
+    }
+    pub(crate) fn evs(&self) -> [u32; 6] {
+        let evs = &self.data.evs_and_condition;
+        [
+            evs.hp,
+            evs.attack,
+            evs.defense,
+            evs.speed,
+            evs.sp_attack,
+            evs.sp_defense,
+        ]
+        .map(u32::from)
+    }
+    /// The level, from the active data if there is some, or else from the experience.
+    pub(crate) fn level(&self, info: &SpeciesInfo) -> u8 {
+        match self.active_data {
+            Some(ref active_data) => active_data.level,
+            None => info.growth_rate.level(self.data.growth.experience),
+        }
+    }
+    /// The stats of the Pokémon, as the game calculates them: HP, Attack, Defense, Speed,
+    /// Special Attack and Special Defense.
+    pub fn calculate_stats(&self, info: &SpeciesInfo) -> [u16; 6] {
+        let level = u32::from(self.level(info));
+        let (ivs, evs) = (self.ivs(), self.evs());
+        let nature = self.personality % 25;
+        let mut stats = [0; 6];
+        for (i, stat) in stats.iter_mut().enumerate() {
+            let base = (2 * u32::from(info.base_stats[i]) + ivs[i] + evs[i] / 4) * level / 100;
+            *stat = if i == 0 {
+                // Shedinja is the only species with a base HP of 1
+                if info.base_stats[0] == 1 {
+                    1
+                } else {
+                    base + level + 10
+                }
+            } else {
+                (base + 5) * nature_multiplier(nature, i as u32 - 1) / 10
+            } as u16;
+        }
+        stats
+    }
+    /// Update the stats of the active data, keeping the damage taken.
+    fn recalculate_stats(&mut self, info: &SpeciesInfo) {
+        let stats = self.calculate_stats(info);
+        let active_data = match self.active_data {
+            Some(ref mut active_data) => active_data,
+            None => return,
+        };
+        if active_data.current_hp != 0 {
+            let current = i32::from(active_data.current_hp) + i32::from(stats[0])
+                - i32::from(active_data.total_hp);
+            active_data.current_hp = current.clamp(1, i32::from(stats[0])) as u16;
+        }
+        active_data.total_hp = stats[0];
+        active_data.attack = stats[1];
+        active_data.defense = stats[2];
+        active_data.speed = stats[3];
+        active_data.sp_attack = stats[4];
+        active_data.sp_defense = stats[5];
+    }
+    /// Whether the nickname is the name of the species, which the game renames on evolution.
+    fn has_species_name(&self) -> bool {
+        self.charset() == Charset::Western
+            && species_name(self.data.growth.species).is_some_and(|name| {
+                rgen3_string::decode_string(&self.nickname.0) == name.to_uppercase()
+            })
+    }
+    /// Change the species, renaming the Pokémon if it's not nicknamed, and updating its stats.
+    fn become_species(&mut self, species: u16, table: &SpeciesTable) -> Result<(), CantEvolve> {
+        let info = table.get(species).ok_or(CantEvolve)?;
+        let rename = self.has_species_name();
+        self.set_species(species).map_err(|_| CantEvolve)?;
+        if rename && let Some(name) = species_name(species) {
+            rgen3_string::encode_string(&name.to_uppercase(), &mut self.nickname.0);
+        }
+        self.recalculate_stats(info);
+        Ok(())
+    }
+    /// The method of the evolution into `target` that `trigger` fulfills.
+    fn evolution_method(
+        &self,
+        target: u16,
+        trigger: EvolutionTrigger,
+        table: &SpeciesTable,
+    ) -> Option<EvolutionMethod> {
+        if self.is_egg() || self.data.growth.item_held == EVERSTONE {
+            return None;
+        }
+        let info = table.get(self.data.growth.species)?;
+        let level = self.level(info);
+        let stats = self.calculate_stats(info);
+        let friendly = self.data.growth.friendship >= MIN_FRIENDSHIP_TO_EVOLVE;
+        let silcoon = (self.personality >> 16) % 10 <= 4;
+        info.evolutions
+            .iter()
+            .filter(|evolution| evolution.target == target)
+            .map(|evolution| evolution.method)
+            .find(|&method| match (trigger, method) {
+                (EvolutionTrigger::LevelUp { .. }, EvolutionMethod::Friendship) => friendly,
+                (EvolutionTrigger::LevelUp { night }, EvolutionMethod::FriendshipDay) => {
+                    friendly && !night
+                }
+                (EvolutionTrigger::LevelUp { night }, EvolutionMethod::FriendshipNight) => {
+                    friendly && night
+                }
+                (
+                    EvolutionTrigger::LevelUp { .. },
+                    EvolutionMethod::Level(min) | EvolutionMethod::LevelNinjask(min),
+                ) => level >= min,
+                (EvolutionTrigger::LevelUp { .. }, EvolutionMethod::LevelAttackHigher(min)) => {
+                    level >= min && stats[1] > stats[2]
+                }
+                (EvolutionTrigger::LevelUp { .. }, EvolutionMethod::LevelAttackEqual(min)) => {
+                    level >= min && stats[1] == stats[2]
+                }
+                (EvolutionTrigger::LevelUp { .. }, EvolutionMethod::LevelDefenseHigher(min)) => {
+                    level >= min && stats[1] < stats[2]
+                }
+                (EvolutionTrigger::LevelUp { .. }, EvolutionMethod::LevelSilcoon(min)) => {
+                    level >= min && silcoon
+                }
+                (EvolutionTrigger::LevelUp { .. }, EvolutionMethod::LevelCascoon(min)) => {
+                    level >= min && !silcoon
+                }
+                (EvolutionTrigger::LevelUp { .. }, EvolutionMethod::Beauty(min)) => {
+                    self.data.evs_and_condition.beauty >= min
+                }
+                (EvolutionTrigger::Trade, EvolutionMethod::Trade) => true,
+                (EvolutionTrigger::Trade, EvolutionMethod::TradeItem(item)) => {
+                    self.data.growth.item_held == item
+                }
+                (EvolutionTrigger::Item(used), EvolutionMethod::Item(item)) => used == item,
+                _ => false,
+            })
+    }
+    /// Evolve into `target`, if `trigger` makes the Pokémon evolve into it according to
+    /// `table`. Like in the game, an Everstone prevents evolving, and eggs can't evolve.
+    ///
+    /// The Pokémon is renamed if it's not nicknamed, and its stats are updated if it has
+    /// active data. The item used by a trade evolution is consumed.
+    ///
+    /// A Nincada evolving doesn't leave a Shedinja behind this way, use
+    /// `Save::evolve_party_pokemon` for that.
+    ///
+    /// Fails if the evolution is not possible, or `table` is missing one of the species.
+    pub fn evolve(
+        &mut self,
+        target: u16,
+        trigger: EvolutionTrigger,
+        table: &SpeciesTable,
+    ) -> Result<(), CantEvolve> {
+        let method = self
+            .evolution_method(target, trigger, table)
+            .ok_or(CantEvolve)?;
+        self.become_species(target, table)?;
+        if let EvolutionMethod::TradeItem(_) = method {
+            self.data.growth.item_held = 0;
+        }
+        Ok(())
+    }
+}
+
+impl Save {
+    /// Evolve the party Pokémon at `party_index`, see `Pokemon::evolve`.
+    ///
+    /// When Nincada evolves into Ninjask and the party has room, a Shedinja is added to it,
+    /// like in the game. It is a copy of Nincada named after its species, without held item,
+    /// markings, ribbons or status. The Pokédex is not updated. Unlike in later generations,
+    /// no Poké Ball is needed for it, so none is taken from the bag.
+    pub fn evolve_party_pokemon(
+        &mut self,
+        party_index: usize,
+        target: u16,
+        trigger: EvolutionTrigger,
+        table: &SpeciesTable,
+    ) -> Result<(), CantEvolve> {
+        let team = self.sections_mut().team;
+        let pokemon = team.get_mut(party_index).ok_or(CantEvolve)?;
+        let method = pokemon
+            .evolution_method(target, trigger, table)
+            .ok_or(CantEvolve)?;
+        let shedinja = table
+            .get(pokemon.data.growth.species)
+            .and_then(|info| {
+                info.evolutions
+                    .iter()
+                    .find(|evolution| matches!(evolution.method, EvolutionMethod::LevelShedinja(_)))
+            })
+            .map(|evolution| evolution.target);
+        let mut left_behind = pokemon.clone();
+        pokemon.evolve(target, trigger, table)?;
+        if let (EvolutionMethod::LevelNinjask(_), Some(shedinja)) = (method, shedinja)
+            && team.len() < PARTY_SIZE
+        {
+            left_behind.data.growth.item_held = 0;
+            left_behind.markings = 0;
+            // Only keep the obedience bit, the unused ribbon bits are cleared too
+            left_behind.data.misc.ribbons_and_obedience &= 0x8000_0000;
+            if let Some(ref mut active_data) = left_behind.active_data {
+                active_data.status_condition = 0;
+                active_data.mail_id = MAIL_NONE;
+            }
+            left_behind.become_species(shedinja, table)?;
+            // Unlike evolving, this always renames
+            if let Some(name) = species_name(shedinja) {
+                rgen3_string::encode_string(&name.to_uppercase(), &mut left_behind.nickname.0);
+            }
+            team.push(left_behind);
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_evolve() {
+    use crate::species::{Evolution, GrowthRate};
+    let info = |evolutions| SpeciesInfo {
+        base_stats: [50; 6],
+        growth_rate: GrowthRate::MediumFast,
+        egg_cycles: 20,
+        evolutions,
+        learnsets: Default::default(),
+    };
+    let mut table = SpeciesTable::default();
+    let evolution = Evolution {
+        method: EvolutionMethod::Level(16),
+        target: 2,
+    };
+    table.insert(1, info(vec![evolution]));
+    table.insert(2, info(Vec::new()));
+    let mut pokemon = Pokemon::default();
+    pokemon.data.growth.species = 1;
+    pokemon.data.growth.experience = GrowthRate::MediumFast.experience(15);
+    let level_up = EvolutionTrigger::LevelUp { night: false };
+    assert!(pokemon.evolve(2, level_up, &table).is_err());
+    pokemon.data.growth.experience = GrowthRate::MediumFast.experience(16);
+    pokemon.data.growth.item_held = EVERSTONE;
+    assert!(pokemon.evolve(2, level_up, &table).is_err());
+    pokemon.data.growth.item_held = 0;
+    assert!(pokemon.evolve(2, EvolutionTrigger::Trade, &table).is_err());
+    pokemon.evolve(2, level_up, &table).unwrap();
+    assert_eq!(pokemon.data.growth.species, 2);
+}
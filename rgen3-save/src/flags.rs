@@ -0,0 +1,252 @@
+//! Event flags and script variables.
+//!
+//! The game scripts keep track of story progress with a big array of one bit flags, and an
+//! array of 16 bit variables. Both are stored in the game state, at an offset depending on
+//! the game.
+
+use crate::{GameType, Save, UnsupportedGame};
+use byteorder::{ByteOrder, LittleEndian as LE};
+
+/// Id of the first script variable. Variables are numbered from here in the game scripts.
+pub const VARS_START: u16 = 0x4000;
+const N_VARS: usize = 256;
+
+/// Offset of the flags in the game state, and their amount in bytes.
+fn flags_location(game: GameType) -> (usize, usize) {
+    match game {
+        GameType::RubyOrSapphire => (0x1220, 0x120),
+        GameType::Emerald => (0x1270, 0x12C),
+        GameType::FireredOrLeafgreen => (0x0EE0, 0x120),
+    }
+}
+
+/// Offset of the variables in the game state.
+fn vars_offset(game: GameType) -> usize {
+    match game {
+        GameType::RubyOrSapphire => 0x1340,
+        GameType::Emerald => 0x139C,
+        GameType::FireredOrLeafgreen => 0x1000,
+    }
+}
+
+/// Flags that are interesting for save editing, with a number that differs between games.
+///
+/// The flags for the HMs received and the legendary Pokémon defeated or caught are not
+/// listed yet: their numbers have to be taken from the decompilations of each game, and
+/// have not been checked against them. Until then, they can be used by number with
+/// `Flags::get` and `Flags::set`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KnownFlag {
+    Badge1,
+    Badge2,
+    Badge3,
+    Badge4,
+    Badge5,
+    Badge6,
+    Badge7,
+    Badge8,
+    /// Got the first Pokémon.
+    PokemonObtained,
+    PokedexObtained,
+    /// The Elite Four has been beaten and the player entered the Hall of Fame.
+    GameClear,
+    /// The Pokédex has been upgraded to the National Dex. The game also checks a variable
+    /// and a value in the trainer info before showing it.
+    NationalDex,
+    /// Emerald only.
+    FrontierPass,
+    /// Fire Red/Leaf Green only. The Sevii Islands map includes One, Two and Three Island.
+    SeviiMap123,
+    /// Fire Red/Leaf Green only. The Sevii Islands map includes Four to Seven Island.
+    SeviiMap4567,
+}
+
+impl KnownFlag {
+    pub const BADGES: [KnownFlag; 8] = [
+        KnownFlag::Badge1,
+        KnownFlag::Badge2,
+        KnownFlag::Badge3,
+        KnownFlag::Badge4,
+        KnownFlag::Badge5,
+        KnownFlag::Badge6,
+        KnownFlag::Badge7,
+        KnownFlag::Badge8,
+    ];
+    /// The number of this flag in `game`, if it exists there.
+    fn number(self, game: GameType) -> Option<u16> {
+        // The system flags come right after the trainer flags, of which Emerald has more.
+        let system_flags = match game {
+            GameType::RubyOrSapphire | GameType::FireredOrLeafgreen => 0x800,
+            GameType::Emerald => 0x860,
+        };
+        let badge = |n: u16| match game {
+            GameType::RubyOrSapphire | GameType::Emerald => system_flags + 0x07 + n,
+            GameType::FireredOrLeafgreen => system_flags + 0x20 + n,
+        };
+        let frlg = game == GameType::FireredOrLeafgreen;
+        Some(match self {
+            KnownFlag::Badge1 => badge(0),
+            KnownFlag::Badge2 => badge(1),
+            KnownFlag::Badge3 => badge(2),
+            KnownFlag::Badge4 => badge(3),
+            KnownFlag::Badge5 => badge(4),
+            KnownFlag::Badge6 => badge(5),
+            KnownFlag::Badge7 => badge(6),
+            KnownFlag::Badge8 => badge(7),
+            KnownFlag::PokemonObtained if frlg => system_flags + 0x28,
+            KnownFlag::PokemonObtained => system_flags,
+            KnownFlag::PokedexObtained if frlg => system_flags + 0x29,
+            KnownFlag::PokedexObtained => system_flags + 0x01,
+            KnownFlag::GameClear if frlg => system_flags + 0x2C,
+            KnownFlag::GameClear => system_flags + 0x04,
+            KnownFlag::NationalDex if frlg => system_flags + 0x40,
+            KnownFlag::NationalDex => system_flags + 0x36,
+            KnownFlag::FrontierPass if game == GameType::Emerald => system_flags + 0x72,
+            KnownFlag::SeviiMap123 if frlg => system_flags + 0x44,
+            KnownFlag::SeviiMap4567 if frlg => system_flags + 0x45,
+            KnownFlag::FrontierPass | KnownFlag::SeviiMap123 | KnownFlag::SeviiMap4567 => {
+                return None;
+            }
+        })
+    }
+}
+
+/// A copy of the event flags of a save.
+///
+/// Get it with `Save::flags`, and write it back with `Save::set_flags`.
+#[derive(Clone, Debug)]
+pub struct Flags {
+    bits: Vec<u8>,
+    game: GameType,
+}
+
+impl Flags {
+    /// Number of flags in this game.
+    pub fn len(&self) -> usize {
+        self.bits.len() * 8
+    }
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+    /// # Panics
+    ///
+    /// Panics if `flag` is not less than `len()`.
+    pub fn get(&self, flag: u16) -> bool {
+        let flag = usize::from(flag);
+        self.bits[flag / 8] & (1 << (flag % 8)) != 0
+    }
+    /// # Panics
+    ///
+    /// Panics if `flag` is not less than `len()`.
+    pub fn set(&mut self, flag: u16, value: bool) {
+        let flag = usize::from(flag);
+        let mask = 1 << (flag % 8);
+        if value {
+            self.bits[flag / 8] |= mask;
+        } else {
+            self.bits[flag / 8] &= !mask;
+        }
+    }
+    pub fn get_known(&self, flag: KnownFlag) -> Result<bool, UnsupportedGame> {
+        flag.number(self.game)
+            .map(|n| self.get(n))
+            .ok_or(UnsupportedGame)
+    }
+    pub fn set_known(&mut self, flag: KnownFlag, value: bool) -> Result<(), UnsupportedGame> {
+        let n = flag.number(self.game).ok_or(UnsupportedGame)?;
+        self.set(n, value);
+        Ok(())
+    }
+}
+
+/// A copy of the script variables of a save.
+///
+/// Get it with `Save::vars`, and write it back with `Save::set_vars`.
+#[derive(Clone, Debug)]
+pub struct Vars {
+    values: Vec<u16>,
+}
+
+impl Vars {
+    /// # Panics
+    ///
+    /// Panics if `var` is not in the range of variables, which starts at `VARS_START`.
+    pub fn get(&self, var: u16) -> u16 {
+        self.values[Self::index(var)]
+    }
+    /// # Panics
+    ///
+    /// Panics if `var` is not in the range of variables, which starts at `VARS_START`.
+    pub fn set(&mut self, var: u16, value: u16) {
+        self.values[Self::index(var)] = value;
+    }
+    fn index(var: u16) -> usize {
+        let index = var.checked_sub(VARS_START).map(usize::from);
+        match index {
+            Some(index) if index < N_VARS => index,
+            _ => panic!("Invalid script variable: 0x{:X}", var),
+        }
+    }
+}
+
+impl Save {
+    pub fn flags(&self) -> Flags {
+        let game = self.game_type();
+        let (offset, len) = flags_location(game);
+        let mut bits = vec![0; len];
+        self.read_game_state(offset, &mut bits);
+        Flags { bits, game }
+    }
+    /// # Panics
+    ///
+    /// Panics if `flags` was not taken from a save of the same game.
+    pub fn set_flags(&mut self, flags: &Flags) {
+        let game = self.game_type();
+        assert_eq!(flags.game, game, "Flags are from a different game");
+        self.write_game_state(flags_location(game).0, &flags.bits);
+    }
+    pub fn vars(&self) -> Vars {
+        let mut raw = [0; N_VARS * 2];
+        self.read_game_state(vars_offset(self.game_type()), &mut raw);
+        let mut values = vec![0; N_VARS];
+        LE::read_u16_into(&raw, &mut values);
+        Vars { values }
+    }
+    pub fn set_vars(&mut self, vars: &Vars) {
+        let mut raw = [0; N_VARS * 2];
+        LE::write_u16_into(&vars.values, &mut raw);
+        self.write_game_state(vars_offset(self.game_type()), &raw);
+    }
+}
+
+#[test]
+fn test_flag_bits() {
+    let mut flags = Flags {
+        bits: vec![0; 4],
+        game: GameType::Emerald,
+    };
+    flags.set(9, true);
+    assert_eq!(flags.bits, [0, 0b10, 0, 0]);
+    assert!(flags.get(9));
+    assert!(!flags.get(8));
+    flags.set(9, false);
+    assert!(!flags.get(9));
+}
+
+#[test]
+fn test_known_flags() {
+    use crate::GameVersion;
+    let mut save = crate::rw::blank_save(GameVersion::FireRed);
+    let mut flags = save.flags();
+    assert_eq!(flags.len(), 0x120 * 8);
+    flags.set_known(KnownFlag::Badge3, true).unwrap();
+    assert!(flags.set_known(KnownFlag::FrontierPass, true).is_err());
+    save.set_flags(&flags);
+    let flags = save.flags();
+    assert!(flags.get(0x822));
+    assert!(flags.get_known(KnownFlag::Badge3).unwrap());
+    let mut vars = save.vars();
+    vars.set(VARS_START + 1, 0x1234);
+    save.set_vars(&vars);
+    assert_eq!(save.vars().get(VARS_START + 1), 0x1234);
+}
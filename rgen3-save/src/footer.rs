@@ -0,0 +1,178 @@
+//! The sectors after the two save blocks.
+//!
+//! They are not rotated like the save blocks, and each kind checks its data its own way:
+//! the Hall of Fame sectors hold a checksum in place of a section id, while the Emerald only
+//! Trainer Hill and Recorded Battle sectors start with a sentinel value instead, and hold a
+//! sum of the bytes of their data.
+
+use crate::rw::checksum;
+use crate::{GameType, Save, UnsupportedGame};
+use byteorder::{ByteOrder, LittleEndian as LE};
+
+pub(crate) const FOOTER_SECTOR_SIZE: usize = 0x1000;
+pub(crate) const N_FOOTER_SECTORS: usize = 4;
+/// Amount of data in a sector that holds a checksum in place of a section id.
+const SECTOR_DATA_SIZE: usize = 3968;
+const SECTOR_CHECKSUM_OFFSET: usize = 0xFF4;
+const SECTOR_SIGNATURE_OFFSET: usize = 0xFF8;
+const SECTOR_SIGNATURE: u32 = 0x0801_2025;
+/// Value at the start of a Trainer Hill or Recorded Battle sector that has been written.
+const SPECIAL_SECTOR_SENTINEL: u32 = 0xB39D;
+/// The Recorded Battle data, ending with a sum of all its other bytes.
+pub(crate) const RECORDED_BATTLE_SIZE: usize = 0xF80;
+/// Where the Trainer Hill data holds the sum of the bytes of its floors, and where the floors
+/// are, after the header and up to the unused padding at the end.
+const TRAINER_HILL_CHECKSUM_OFFSET: usize = 4;
+const TRAINER_HILL_FLOORS: std::ops::Range<usize> = 8..0xEC0;
+/// Offset of the best times of each Trainer Hill mode in the game state. Emerald only.
+const TRAINER_HILL_TIMES_OFFSET: usize = 0x3718;
+
+/// What a sector of the footer is used for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FooterSectorKind {
+    HallOfFame,
+    /// Trainer Hill data scanned from e-Reader cards. Emerald only.
+    TrainerHill,
+    /// The battle saved at the Battle Frontier or after a link battle. Emerald only.
+    RecordedBattle,
+    Unused,
+}
+
+impl FooterSectorKind {
+    fn of_sector(game: GameType, index: usize) -> Self {
+        match (index, game) {
+            (0, _) | (1, _) => FooterSectorKind::HallOfFame,
+            (2, GameType::Emerald) => FooterSectorKind::TrainerHill,
+            (3, GameType::Emerald) => FooterSectorKind::RecordedBattle,
+            _ => FooterSectorKind::Unused,
+        }
+    }
+}
+
+/// Whether the data of a sector can be trusted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SectorStatus {
+    /// Never written by the game.
+    Empty,
+    Valid,
+    /// Fails its checksum.
+    Corrupt,
+}
+
+fn byte_sum(data: &[u8]) -> u32 {
+    data.iter().map(|&b| u32::from(b)).sum()
+}
+
+pub(crate) struct FooterSector {
+    pub(crate) data: [u8; FOOTER_SECTOR_SIZE],
+}
+
+impl FooterSector {
+    /// The data of a sector that holds its checksum in place of a section id.
+    pub(crate) fn checksummed_data(&self) -> (SectorStatus, &[u8]) {
+        let data = &self.data[..SECTOR_DATA_SIZE];
+        let status = if LE::read_u32(&self.data[SECTOR_SIGNATURE_OFFSET..]) != SECTOR_SIGNATURE {
+            SectorStatus::Empty
+        } else if LE::read_u16(&self.data[SECTOR_CHECKSUM_OFFSET..]) != checksum(data) {
+            SectorStatus::Corrupt
+        } else {
+            SectorStatus::Valid
+        };
+        (status, data)
+    }
+    pub(crate) fn set_checksummed_data(&mut self, data: &[u8]) {
+        // The game clears the whole sector before writing it
+        self.data = [0; FOOTER_SECTOR_SIZE];
+        self.data[..SECTOR_DATA_SIZE].copy_from_slice(data);
+        LE::write_u16(&mut self.data[SECTOR_CHECKSUM_OFFSET..], checksum(data));
+        LE::write_u32(&mut self.data[SECTOR_SIGNATURE_OFFSET..], SECTOR_SIGNATURE);
+    }
+    /// The data of a Trainer Hill or Recorded Battle sector, if it has been written.
+    pub(crate) fn special_data(&self) -> Option<&[u8]> {
+        if LE::read_u32(&self.data) == SPECIAL_SECTOR_SENTINEL {
+            Some(&self.data[4..])
+        } else {
+            None
+        }
+    }
+    fn status(&self, kind: FooterSectorKind) -> SectorStatus {
+        match kind {
+            FooterSectorKind::HallOfFame => self.checksummed_data().0,
+            FooterSectorKind::TrainerHill => match self.special_data() {
+                Some(data) => {
+                    let sum = LE::read_u32(&data[TRAINER_HILL_CHECKSUM_OFFSET..]);
+                    if byte_sum(&data[TRAINER_HILL_FLOORS]) == sum {
+                        SectorStatus::Valid
+                    } else {
+                        SectorStatus::Corrupt
+                    }
+                }
+                None => SectorStatus::Empty,
+            },
+            FooterSectorKind::RecordedBattle => match self.special_data() {
+                Some(data) => {
+                    let (data, sum) =
+                        data[..RECORDED_BATTLE_SIZE].split_at(RECORDED_BATTLE_SIZE - 4);
+                    if byte_sum(data) == LE::read_u32(sum) {
+                        SectorStatus::Valid
+                    } else {
+                        SectorStatus::Corrupt
+                    }
+                }
+                None => SectorStatus::Empty,
+            },
+            FooterSectorKind::Unused => SectorStatus::Empty,
+        }
+    }
+}
+
+impl Save {
+    /// What each sector of the footer is used for, and whether its data is valid.
+    pub fn footer_sectors(&self) -> Vec<(FooterSectorKind, SectorStatus)> {
+        let game = self.game_type();
+        self.footer
+            .iter()
+            .enumerate()
+            .map(|(i, sector)| {
+                let kind = FooterSectorKind::of_sector(game, i);
+                (kind, sector.status(kind))
+            })
+            .collect()
+    }
+    /// The best time of each Trainer Hill mode, in frames. Emerald only.
+    ///
+    /// The modes are Normal, Variety, Unique and Expert, in this order.
+    pub fn trainer_hill_times(&self) -> Result<[u32; 4], UnsupportedGame> {
+        if self.game_type() != GameType::Emerald {
+            return Err(UnsupportedGame);
+        }
+        let mut raw = [0; 16];
+        self.read_game_state(TRAINER_HILL_TIMES_OFFSET, &mut raw);
+        let mut times = [0; 4];
+        LE::read_u32_into(&raw, &mut times);
+        Ok(times)
+    }
+}
+
+#[test]
+fn test_special_sector_status() {
+    let mut sector = FooterSector {
+        data: [0; FOOTER_SECTOR_SIZE],
+    };
+    let kind = FooterSectorKind::TrainerHill;
+    assert_eq!(sector.status(kind), SectorStatus::Empty);
+    LE::write_u32(&mut sector.data, SPECIAL_SECTOR_SENTINEL);
+    // The data starts after the sentinel
+    let floors = 4 + TRAINER_HILL_FLOORS.start;
+    sector.data[floors] = 0x12;
+    sector.data[floors + 0x100] = 0x34;
+    LE::write_u32(&mut sector.data[4 + TRAINER_HILL_CHECKSUM_OFFSET..], 0x46);
+    assert_eq!(sector.status(kind), SectorStatus::Valid);
+    sector.data[floors + 1] = 1;
+    assert_eq!(sector.status(kind), SectorStatus::Corrupt);
+    let kind = FooterSectorKind::RecordedBattle;
+    sector.data[4..].fill(0);
+    sector.data[4] = 0xFF;
+    LE::write_u32(&mut sector.data[RECORDED_BATTLE_SIZE..], 0xFF);
+    assert_eq!(sector.status(kind), SectorStatus::Valid);
+}
@@ -0,0 +1,143 @@
+//! Byte level access to the game state, which the game keeps as one big structure that is
+//! split across sections 1 to 4 when saving.
+
+use crate::{
+    GameType, Save, SaveBlock, SectionData, TEAMANDITEMS_POKE_LEN, TeamAndItems,
+    TeamAndItemsRemaining, TeamAndItemsUnknown,
+};
+
+/// Amount of game state data each section holds.
+const GAME_STATE_SECTION_LEN: usize = 3968;
+
+impl TeamAndItems {
+    /// The data before and after the team.
+    fn raw_parts(&self) -> (&[u8], &[u8]) {
+        let unknown: &[u8] = match self.unknown {
+            TeamAndItemsUnknown::EmeraldOrRubyOrSapphire(ref data) => data,
+            TeamAndItemsUnknown::FireRedOrLeafGreen(ref data) => data,
+        };
+        let remaining: &[u8] = match self.remaining_data {
+            TeamAndItemsRemaining::EmeraldOrRubyOrSapphire(ref data) => data,
+            TeamAndItemsRemaining::FireredOrLeafgreen(ref data) => data,
+        };
+        (unknown, remaining)
+    }
+    fn raw_parts_mut(&mut self) -> (&mut [u8], &mut [u8]) {
+        let unknown: &mut [u8] = match self.unknown {
+            TeamAndItemsUnknown::EmeraldOrRubyOrSapphire(ref mut data) => data,
+            TeamAndItemsUnknown::FireRedOrLeafGreen(ref mut data) => data,
+        };
+        let remaining: &mut [u8] = match self.remaining_data {
+            TeamAndItemsRemaining::EmeraldOrRubyOrSapphire(ref mut data) => data,
+            TeamAndItemsRemaining::FireredOrLeafgreen(ref mut data) => data,
+        };
+        (unknown, remaining)
+    }
+}
+
+/// Find the position of `offset` in either the data before or after the team.
+fn locate_around_team(unknown_len: usize, offset: usize) -> (bool, usize) {
+    if offset < unknown_len {
+        return (false, offset);
+    }
+    let remaining_start = unknown_len + 4 + TEAMANDITEMS_POKE_LEN;
+    assert!(
+        offset >= remaining_start,
+        "Team data at offset {} can't be accessed as raw game state",
+        offset
+    );
+    (true, offset - remaining_start)
+}
+
+impl SaveBlock {
+    fn game_state_byte(&self, offset: usize) -> u8 {
+        let (section, offset) = (
+            offset / GAME_STATE_SECTION_LEN,
+            offset % GAME_STATE_SECTION_LEN,
+        );
+        if section == 0 {
+            let team_and_items = match self.sections[self.team_and_items_index].data {
+                SectionData::TeamAndItems(ref data) => data,
+                _ => panic!("Unexpected section data. Expected TeamAndItems"),
+            };
+            let (unknown, remaining) = team_and_items.raw_parts();
+            match locate_around_team(unknown.len(), offset) {
+                (false, offset) => unknown[offset],
+                (true, offset) => remaining[offset],
+            }
+        } else {
+            let index = self.game_state_indexes[section - 1];
+            match self.sections[index].data {
+                SectionData::GameState(ref buf) => buf.data[offset],
+                _ => panic!("Unexpected section data. Expected GameState"),
+            }
+        }
+    }
+    fn game_state_byte_mut(&mut self, offset: usize) -> &mut u8 {
+        let (section, offset) = (
+            offset / GAME_STATE_SECTION_LEN,
+            offset % GAME_STATE_SECTION_LEN,
+        );
+        if section == 0 {
+            let team_and_items = match self.sections[self.team_and_items_index].data {
+                SectionData::TeamAndItems(ref mut data) => data,
+                _ => panic!("Unexpected section data. Expected TeamAndItems"),
+            };
+            let (unknown, remaining) = team_and_items.raw_parts_mut();
+            match locate_around_team(unknown.len(), offset) {
+                (false, offset) => &mut unknown[offset],
+                (true, offset) => &mut remaining[offset],
+            }
+        } else {
+            let index = self.game_state_indexes[section - 1];
+            match self.sections[index].data {
+                SectionData::GameState(ref mut buf) => &mut buf.data[offset],
+                _ => panic!("Unexpected section data. Expected GameState"),
+            }
+        }
+    }
+}
+
+impl Save {
+    pub(crate) fn game_type(&self) -> GameType {
+        GameType::from(&self.sections().trainer.game)
+    }
+    /// Fill `buf` with game state data starting at `offset`.
+    pub(crate) fn read_game_state(&self, offset: usize, buf: &mut [u8]) {
+        let block = &self.blocks[self.most_recent_index];
+        for (i, b) in buf.iter_mut().enumerate() {
+            *b = block.game_state_byte(offset + i);
+        }
+    }
+    /// Overwrite the game state data starting at `offset` with `data`.
+    pub(crate) fn write_game_state(&mut self, offset: usize, data: &[u8]) {
+        let block = &mut self.blocks[self.most_recent_index];
+        for (i, &b) in data.iter().enumerate() {
+            *block.game_state_byte_mut(offset + i) = b;
+        }
+    }
+}
+
+#[test]
+fn test_game_state_roundtrip() {
+    use crate::GameVersion;
+    use std::io::Cursor;
+    let mut save = crate::rw::blank_save(GameVersion::Emerald);
+    // Spans the end of section 2 and the start of section 3
+    let offset = 2 * GAME_STATE_SECTION_LEN - 2;
+    save.write_game_state(offset, &[1, 2, 3, 4]);
+    // After the team, in section 1
+    let after_team = crate::EM_RU_SA_TEAMANDITEMS_UNK_LEN + 4 + TEAMANDITEMS_POKE_LEN;
+    save.write_game_state(after_team, &[5]);
+    let mut data = Vec::new();
+    save.write(&mut data).unwrap();
+    // The second block is the most recent one, with the sections in order
+    let section = |id: usize| &data[(14 + id) * 0x1000..];
+    assert_eq!(section(2)[GAME_STATE_SECTION_LEN - 2..][..2], [1, 2]);
+    assert_eq!(section(3)[..2], [3, 4]);
+    assert_eq!(section(1)[after_team], 5);
+    let save = Save::read(&mut Cursor::new(data)).unwrap();
+    let mut buf = [0; 4];
+    save.read_game_state(offset, &mut buf);
+    assert_eq!(buf, [1, 2, 3, 4]);
+}
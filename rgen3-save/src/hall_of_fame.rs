@@ -0,0 +1,184 @@
+//! The Hall of Fame, kept in the first two sectors of the save footer.
+
+use crate::footer::SectorStatus;
+use crate::{POKEMON_NICK_LEN, Pokemon, PokemonNick, Save};
+use byteorder::{ByteOrder, LittleEndian as LE};
+
+/// Amount of Hall of Fame data in each sector.
+const SECTOR_DATA_SIZE: usize = 3968;
+const N_SECTORS: usize = 2;
+
+const HOF_MON_SIZE: usize = 20;
+const HOF_TEAM_SIZE: usize = 6;
+/// The game drops the oldest entries once there are this many.
+pub const HALL_OF_FAME_MAX_ENTRIES: usize = 50;
+
+/// A Pokémon that entered the Hall of Fame.
+#[derive(Clone, Copy, Debug)]
+pub struct HallOfFameMon {
+    pub ot_id: u32,
+    pub personality: u32,
+    /// Species index. Only 9 bits are stored.
+    pub species: u16,
+    /// Only 7 bits are stored.
+    pub level: u8,
+    pub nickname: PokemonNick,
+}
+
+impl HallOfFameMon {
+    /// Record a party Pokémon. Pokémon without active data are recorded at level 0.
+    pub fn from_pokemon(pokemon: &Pokemon) -> Self {
+        HallOfFameMon {
+            ot_id: pokemon.ot_id,
+            personality: pokemon.personality,
+            species: pokemon.data.growth.species,
+            level: pokemon.active_data.as_ref().map_or(0, |data| data.level),
+            nickname: pokemon.nickname,
+        }
+    }
+    fn read(data: &[u8]) -> Self {
+        let species_and_level = LE::read_u16(&data[8..]);
+        let mut nickname = PokemonNick::default();
+        nickname.0.copy_from_slice(&data[10..10 + POKEMON_NICK_LEN]);
+        HallOfFameMon {
+            ot_id: LE::read_u32(&data[0..]),
+            personality: LE::read_u32(&data[4..]),
+            species: species_and_level & 0x1FF,
+            level: (species_and_level >> 9) as u8,
+            nickname,
+        }
+    }
+    fn write(&self, data: &mut [u8]) {
+        LE::write_u32(&mut data[0..], self.ot_id);
+        LE::write_u32(&mut data[4..], self.personality);
+        let species_and_level = (self.species & 0x1FF) | (u16::from(self.level & 0x7F) << 9);
+        LE::write_u16(&mut data[8..], species_and_level);
+        data[10..10 + POKEMON_NICK_LEN].copy_from_slice(&self.nickname.0);
+    }
+}
+
+/// A team that entered the Hall of Fame, of up to 6 Pokémon.
+#[derive(Clone, Debug, Default)]
+pub struct HallOfFameEntry {
+    pub team: Vec<HallOfFameMon>,
+}
+
+impl HallOfFameEntry {
+    /// Record a party.
+    pub fn from_team(team: &[Pokemon]) -> Self {
+        HallOfFameEntry {
+            team: team.iter().map(HallOfFameMon::from_pokemon).collect(),
+        }
+    }
+}
+
+/// Error returned when the Hall of Fame data fails its checksum.
+#[derive(Debug)]
+pub struct CorruptHallOfFame;
+
+impl Save {
+    /// The data of both Hall of Fame sectors, one after the other, or `None` if the game hasn't
+    /// written them yet.
+    fn hall_of_fame_data(&self) -> Result<Option<Vec<u8>>, CorruptHallOfFame> {
+        let mut data = Vec::with_capacity(N_SECTORS * SECTOR_DATA_SIZE);
+        for sector in &self.footer[..N_SECTORS] {
+            match sector.checksummed_data() {
+                (SectorStatus::Empty, _) => return Ok(None),
+                (SectorStatus::Corrupt, _) => return Err(CorruptHallOfFame),
+                (SectorStatus::Valid, sector_data) => data.extend_from_slice(sector_data),
+            }
+        }
+        Ok(Some(data))
+    }
+    /// The teams that entered the Hall of Fame, oldest first.
+    pub fn hall_of_fame(&self) -> Result<Vec<HallOfFameEntry>, CorruptHallOfFame> {
+        let data = match self.hall_of_fame_data()? {
+            Some(data) => data,
+            None => return Ok(Vec::new()),
+        };
+        let entries = data
+            .chunks(HOF_MON_SIZE * HOF_TEAM_SIZE)
+            .take(HALL_OF_FAME_MAX_ENTRIES)
+            .map(|team| {
+                team.chunks(HOF_MON_SIZE)
+                    .map(HallOfFameMon::read)
+                    .take_while(|mon| mon.species != 0)
+                    .collect::<Vec<_>>()
+            })
+            .take_while(|team| !team.is_empty())
+            .map(|team| HallOfFameEntry { team })
+            .collect();
+        Ok(entries)
+    }
+    /// Replace the Hall of Fame, recomputing the checksums.
+    ///
+    /// Only the last `HALL_OF_FAME_MAX_ENTRIES` entries are kept, like the game does.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a team has more than 6 Pokémon.
+    pub fn set_hall_of_fame(&mut self, entries: &[HallOfFameEntry]) {
+        let mut data = vec![0; N_SECTORS * SECTOR_DATA_SIZE];
+        let skip = entries.len().saturating_sub(HALL_OF_FAME_MAX_ENTRIES);
+        let teams = data.chunks_mut(HOF_MON_SIZE * HOF_TEAM_SIZE);
+        for (entry, team_data) in entries[skip..].iter().zip(teams) {
+            assert!(
+                entry.team.len() <= HOF_TEAM_SIZE,
+                "Hall of Fame team with more than {} Pokémon",
+                HOF_TEAM_SIZE
+            );
+            for (mon, mon_data) in entry.team.iter().zip(team_data.chunks_mut(HOF_MON_SIZE)) {
+                mon.write(mon_data);
+            }
+        }
+        for (sector, sector_data) in self.footer.iter_mut().zip(data.chunks(SECTOR_DATA_SIZE)) {
+            sector.set_checksummed_data(sector_data);
+        }
+    }
+    /// Add a team to the Hall of Fame, dropping the oldest one if it's full.
+    pub fn add_hall_of_fame_entry(
+        &mut self,
+        entry: HallOfFameEntry,
+    ) -> Result<(), CorruptHallOfFame> {
+        let mut entries = self.hall_of_fame()?;
+        entries.push(entry);
+        self.set_hall_of_fame(&entries);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_hall_of_fame_roundtrip() {
+    use crate::GameVersion;
+    use std::io::Cursor;
+    let mut save = crate::rw::blank_save(GameVersion::Emerald);
+    assert!(save.hall_of_fame().unwrap().is_empty());
+    let mon = |species: u16| HallOfFameMon {
+        ot_id: 0x1234_5678,
+        personality: u32::from(species),
+        species,
+        level: 100,
+        nickname: PokemonNick([0xBB; POKEMON_NICK_LEN]),
+    };
+    for species in 1..=HALL_OF_FAME_MAX_ENTRIES as u16 + 1 {
+        let entry = HallOfFameEntry {
+            team: vec![mon(species), mon(411)],
+        };
+        save.add_hall_of_fame_entry(entry).unwrap();
+    }
+    let mut data = Vec::new();
+    save.write(&mut data).unwrap();
+    let mut save = Save::read(&mut Cursor::new(data)).unwrap();
+    let entries = save.hall_of_fame().unwrap();
+    assert_eq!(entries.len(), HALL_OF_FAME_MAX_ENTRIES);
+    // The oldest team was dropped
+    let first = &entries[0].team;
+    assert_eq!(first.len(), 2);
+    assert_eq!(first[0].species, 2);
+    assert_eq!(first[0].personality, 2);
+    assert_eq!(first[1].species, 411);
+    assert_eq!(first[1].level, 100);
+    assert_eq!(first[1].nickname.0, [0xBB; POKEMON_NICK_LEN]);
+    save.footer[1].data[0] ^= 1;
+    assert!(save.hall_of_fame().is_err());
+}
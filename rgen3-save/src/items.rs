@@ -0,0 +1,126 @@
+//! Items, and the item held by a Pokémon.
+
+use crate::mail::is_mail_item;
+use crate::{GameType, GameVersion, Pokemon};
+
+const ENIGMA_BERRY: u16 = 175;
+
+/// Item ids that are in every game's item list but are unused, named "????????".
+fn is_unused(id: u16) -> bool {
+    matches!(
+        id,
+        52..=62
+            | 72
+            | 82
+            | 87..=92
+            | 99..=102
+            | 105
+            | 112..=120
+            | 176..=178
+            | 226..=253
+            | 267
+            | 347
+            | 348
+    )
+}
+
+/// The highest item id of `game`.
+fn last_item(game: GameType) -> u16 {
+    match game {
+        // Ends with the HMs
+        GameType::RubyOrSapphire => 346,
+        // Ends with the Sapphire
+        GameType::FireredOrLeafgreen => 374,
+        // Ends with the Old Sea Map
+        GameType::Emerald => 376,
+    }
+}
+
+/// An item that exists in a game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Item(u16);
+
+/// Error returned when an item id is not one of the game's items.
+#[derive(Debug)]
+pub struct InvalidItem;
+
+impl Item {
+    /// The item with id `id` in `game`.
+    pub fn new(id: u16, game: GameVersion) -> Result<Self, InvalidItem> {
+        if id == 0 || id > last_item(game.game_type()) || is_unused(id) {
+            return Err(InvalidItem);
+        }
+        Ok(Item(id))
+    }
+    pub fn id(self) -> u16 {
+        self.0
+    }
+    /// Whether this is one of the mail items, which carry a message.
+    pub fn is_mail(self) -> bool {
+        is_mail_item(self.0)
+    }
+    pub fn is_berry(self) -> bool {
+        (133..=ENIGMA_BERRY).contains(&self.0)
+    }
+    /// Whether this is the Enigma Berry. Its name and effect are not part of the game, but come
+    /// from the Enigma Berry received through Mystery Gift, see `Save::enigma_berry`.
+    pub fn is_enigma_berry(self) -> bool {
+        self.0 == ENIGMA_BERRY
+    }
+}
+
+/// Error returned when a Pokémon can't be given an item.
+#[derive(Debug)]
+pub struct CantHoldItem;
+
+impl Pokemon {
+    /// The item the Pokémon holds, if any. It is not checked against the game's items.
+    pub fn held_item(&self) -> Option<Item> {
+        match self.data.growth.item_held {
+            0 => None,
+            id => Some(Item(id)),
+        }
+    }
+    /// Give the Pokémon `item` to hold, or take its item away with `None`.
+    ///
+    /// Eggs can't hold items. Mail can't be given this way, as it needs a message: use
+    /// `Save::attach_mail`. Taking mail away this way leaves the message behind, use
+    /// `Save::take_mail` instead.
+    pub fn set_held_item(&mut self, item: Option<Item>) -> Result<(), CantHoldItem> {
+        match item {
+            Some(_) if self.is_egg() => Err(CantHoldItem),
+            Some(item) if item.is_mail() => Err(CantHoldItem),
+            Some(item) => {
+                self.data.growth.item_held = item.0;
+                Ok(())
+            }
+            None => {
+                self.data.growth.item_held = 0;
+                Ok(())
+            }
+        }
+    }
+}
+
+#[test]
+fn test_items() {
+    let (ruby, emerald) = (GameVersion::Ruby, GameVersion::Emerald);
+    assert!(Item::new(0, emerald).is_err());
+    assert!(Item::new(52, emerald).is_err());
+    // The Old Sea Map only exists in Emerald
+    assert!(Item::new(376, emerald).is_ok());
+    assert!(Item::new(376, ruby).is_err());
+    assert!(Item::new(ENIGMA_BERRY, ruby).unwrap().is_enigma_berry());
+    let mut pokemon = Pokemon::default();
+    let mail = Item::new(121, ruby).unwrap();
+    assert!(mail.is_mail());
+    assert!(pokemon.set_held_item(Some(mail)).is_err());
+    let berry = Item::new(133, ruby).unwrap();
+    assert!(berry.is_berry());
+    pokemon.set_held_item(Some(berry)).unwrap();
+    assert_eq!(pokemon.held_item(), Some(berry));
+    pokemon.set_held_item(None).unwrap();
+    assert_eq!(pokemon.held_item(), None);
+    pokemon.data.misc.ivs_eggs_and_ability = 1 << 30;
+    assert!(pokemon.set_held_item(Some(berry)).is_err());
+}
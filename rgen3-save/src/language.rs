@@ -0,0 +1,127 @@
+//! The language and markings of a Pokémon.
+
+use crate::Pokemon;
+use rgen3_string::Charset;
+use std::ops::BitOr;
+
+/// The language of the game a Pokémon comes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Language {
+    Japanese = 1,
+    English,
+    French,
+    Italian,
+    German,
+    // 6 is Korean, which has no Gen3 games
+    Spanish = 7,
+}
+
+impl Language {
+    fn from_u8(value: u8) -> Option<Self> {
+        use Language::*;
+        match value {
+            1 => Some(Japanese),
+            2 => Some(English),
+            3 => Some(French),
+            4 => Some(Italian),
+            5 => Some(German),
+            7 => Some(Spanish),
+            _ => None,
+        }
+    }
+    /// The character set names are encoded with in this language.
+    pub fn charset(self) -> Charset {
+        match self {
+            Language::Japanese => Charset::Japanese,
+            _ => Charset::Western,
+        }
+    }
+}
+
+/// The markings shown on a Pokémon in the PC, as a set of bits.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Markings(u8);
+
+impl Markings {
+    pub const CIRCLE: Markings = Markings(0x1);
+    pub const SQUARE: Markings = Markings(0x2);
+    pub const TRIANGLE: Markings = Markings(0x4);
+    pub const HEART: Markings = Markings(0x8);
+    pub fn empty() -> Self {
+        Markings(0)
+    }
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+    pub fn contains(self, other: Markings) -> bool {
+        self.0 & other.0 == other.0
+    }
+    pub fn insert(&mut self, other: Markings) {
+        self.0 |= other.0;
+    }
+    pub fn remove(&mut self, other: Markings) {
+        self.0 &= !other.0;
+    }
+}
+
+impl BitOr for Markings {
+    type Output = Markings;
+    fn bitor(self, rhs: Markings) -> Markings {
+        Markings(self.0 | rhs.0)
+    }
+}
+
+impl Pokemon {
+    /// `None` if the language is not a known one.
+    pub fn language(&self) -> Option<Language> {
+        Language::from_u8(self.language as u8)
+    }
+    pub fn set_language(&mut self, language: Language) {
+        // The high byte holds flags, like whether the Pokémon is an egg
+        self.language = (self.language & 0xFF00) | language as u16;
+    }
+    /// The charset the names of the Pokémon are encoded with. Western for unknown languages.
+    pub fn charset(&self) -> Charset {
+        self.language()
+            .map_or(Charset::Western, |language| language.charset())
+    }
+    /// The nickname, decoded according to the language of the Pokémon.
+    pub fn nickname_text(&self) -> String {
+        rgen3_string::decode_string_with(&self.nickname.0, self.charset())
+    }
+    pub fn markings(&self) -> Markings {
+        Markings(self.markings & 0xF)
+    }
+    pub fn set_markings(&mut self, markings: Markings) {
+        self.markings = markings.0;
+    }
+}
+
+#[test]
+fn test_language_and_markings() {
+    let mut pokemon = Pokemon {
+        language: 0x0602,
+        ..Default::default()
+    };
+    assert_eq!(pokemon.language(), Some(Language::English));
+    pokemon.set_language(Language::Japanese);
+    assert_eq!(pokemon.language, 0x0601);
+    assert_eq!(pokemon.charset(), Charset::Japanese);
+    pokemon.language = 6;
+    assert_eq!(pokemon.language(), None);
+    assert_eq!(pokemon.charset(), Charset::Western);
+    let mut markings = Markings::CIRCLE | Markings::HEART;
+    assert_eq!(markings.bits(), 0x9);
+    markings.remove(Markings::CIRCLE);
+    markings.insert(Markings::SQUARE);
+    pokemon.set_markings(markings);
+    assert!(
+        pokemon
+            .markings()
+            .contains(Markings::SQUARE | Markings::HEART)
+    );
+    assert!(!pokemon.markings().contains(Markings::CIRCLE));
+    // Only the low 4 bits are markings
+    pokemon.markings = 0xF2;
+    assert_eq!(pokemon.markings(), Markings::SQUARE);
+}
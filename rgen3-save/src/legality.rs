@@ -0,0 +1,267 @@
+//! Checks for Pokémon that can't have been obtained legitimately.
+
+use crate::moves::learns;
+use crate::species::SpeciesTable;
+use crate::{Move, Pokemon};
+
+const MAX_TOTAL_EVS: u32 = 510;
+const OBEDIENCE_BIT: u32 = 1 << 31;
+/// Ribbon bits that no ribbon uses.
+const UNUSED_RIBBON_BITS: u32 = 0xF << 27;
+/// The highest rank of a contest ribbon, Master.
+const MAX_CONTEST_RANK: u32 = 4;
+const N_CONTEST_CATEGORIES: u32 = 5;
+const POKE_BALL: u8 = 4;
+const SAFARI_BALL: u8 = 5;
+const PREMIER_BALL: u8 = 12;
+/// Origin game code of Colosseum and XD.
+const ORIGIN_GAMECUBE: u16 = 15;
+/// Met locations of the Safari Zones of Hoenn and Kanto.
+const HOENN_SAFARI_ZONE: u8 = 0x39;
+const KANTO_SAFARI_ZONE: u8 = 0x88;
+
+/// Something wrong with a Pokémon.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LegalityProblem {
+    /// The IVs can't have been generated along with the personality by Method 1, 2 or 4,
+    /// which wild and most gift Pokémon are made with. This is only a warning, see
+    /// `is_warning`.
+    PidIvMismatch,
+    /// The EVs add up to more than 510.
+    TooManyEvs(u32),
+    /// The species can't learn the move, nor any species it evolves from.
+    IllegalMove(u16),
+    /// The ball it was caught in can't be used where it was met.
+    UnavailableBall(u8),
+    /// It was met at a higher level than its current level.
+    MetAboveLevel { met: u8, level: u8 },
+    /// It has ribbons it can't have, like ribbon bits no ribbon uses, contest ribbons above
+    /// Master rank, or ribbons on an egg.
+    ImpossibleRibbons,
+    /// The checksum read from the save didn't match the data. Only Pokémon read from a save
+    /// are checked, as the checksum is computed when writing them.
+    ChecksumMismatch,
+}
+
+impl LegalityProblem {
+    /// Whether the problem can have a legitimate cause, so the Pokémon is only suspicious.
+    ///
+    /// The personality and IVs of some gift Pokémon, like the ones of the Colosseum bonus
+    /// disc, are made by other methods than the ones checked, and the game of origin and met
+    /// location don't always tell them apart from wild ones.
+    pub fn is_warning(self) -> bool {
+        self == LegalityProblem::PidIvMismatch
+    }
+}
+
+/// Whether `species` roams in the wild of `origin_game`.
+///
+/// The games only keep part of the IVs of a roaming Pokémon, so the ones it's caught with
+/// don't match its personality.
+fn is_roamer(species: u16, origin_game: u16) -> bool {
+    match origin_game {
+        // Latias, Latios
+        1..=3 => matches!(species, 407 | 408),
+        // Raikou, Entei, Suicune
+        4 | 5 => matches!(species, 243..=245),
+        _ => false,
+    }
+}
+
+/// Whether a Pokémon met at `met_location` in `origin_game` can be in `ball`.
+///
+/// Balls can be traded between games as held items, so the ones a game doesn't sell can still
+/// be used in it, but Safari Balls can't be used outside of the Safari Zone, and Colosseum/XD
+/// have no Safari Zone. The origin game is expected to be a known one.
+fn ball_available(ball: u8, origin_game: u16, met_location: u8) -> bool {
+    match origin_game {
+        // Ruby, Sapphire, Emerald
+        1..=3 if ball == SAFARI_BALL => met_location == HOENN_SAFARI_ZONE,
+        // Fire Red, Leaf Green
+        4 | 5 if ball == SAFARI_BALL => met_location == KANTO_SAFARI_ZONE,
+        ORIGIN_GAMECUBE if ball == SAFARI_BALL => false,
+        _ => (1..=PREMIER_BALL).contains(&ball),
+    }
+}
+
+/// One step of the random number generator the games use.
+fn lcg(seed: u32) -> u32 {
+    seed.wrapping_mul(0x41C6_4E6D).wrapping_add(0x6073)
+}
+
+/// Whether `ivs` can come with `personality` by Method 1, 2 or 4.
+///
+/// The personality is made of two random numbers, the lower half first. The IVs are the two
+/// random numbers that follow, with an extra call before the first one (Method 2) or the
+/// second one (Method 4).
+fn pid_iv_match(personality: u32, ivs: u32) -> bool {
+    let (first_ivs, second_ivs) = (ivs & 0x7FFF, (ivs >> 15) & 0x7FFF);
+    let rand = |seed: u32| seed >> 16;
+    (0..=0xFFFF).any(|low| {
+        let seed = ((personality & 0xFFFF) << 16) | low;
+        let seed = lcg(seed);
+        if rand(seed) != personality >> 16 {
+            return false;
+        }
+        let (a, b, c) = (lcg(seed), lcg(lcg(seed)), lcg(lcg(lcg(seed))));
+        let method_1 = rand(a) & 0x7FFF == first_ivs && rand(b) & 0x7FFF == second_ivs;
+        let method_2 = rand(b) & 0x7FFF == first_ivs && rand(c) & 0x7FFF == second_ivs;
+        let method_4 = rand(a) & 0x7FFF == first_ivs && rand(c) & 0x7FFF == second_ivs;
+        method_1 || method_2 || method_4
+    })
+}
+
+impl Pokemon {
+    /// Look for anything that shows the Pokémon was not obtained legitimately.
+    ///
+    /// Moves and levels are checked against `species`, and are not checked if the species of
+    /// the Pokémon is not in it. A move is legal if it is in the learnset of any game, as the
+    /// Pokémon can have been traded to it. The personality and IVs are only checked for
+    /// Pokémon met in the wild of a Gen3 game, as eggs and event Pokémon are made differently,
+    /// and roaming Pokémon lose part of their IVs.
+    pub fn legality_problems(&self, species: &SpeciesTable) -> Vec<LegalityProblem> {
+        let mut problems = Vec::new();
+        let misc = &self.data.misc;
+        let met_level = (misc.origins_info & 0x7F) as u8;
+        let origin_game = misc.origin_game();
+        let ball = ((misc.origins_info >> 11) & 0xF) as u8;
+        let from_event = misc.ribbons_and_obedience & OBEDIENCE_BIT != 0;
+        let ivs = misc.ivs_eggs_and_ability & 0x3FFF_FFFF;
+        if met_level != 0
+            && (1..=5).contains(&origin_game)
+            && !from_event
+            && !is_roamer(self.data.growth.species, origin_game)
+            && !pid_iv_match(self.personality, ivs)
+        {
+            problems.push(LegalityProblem::PidIvMismatch);
+        }
+        let evs: u32 = self.evs().iter().sum();
+        if evs > MAX_TOTAL_EVS {
+            problems.push(LegalityProblem::TooManyEvs(evs));
+        }
+        if let Some(info) = species.get(self.data.growth.species) {
+            let level = self.level(info);
+            // The Pokémon may have learned its moves in any game it was traded to
+            let mut learnsets: Vec<_> = info.learnsets.iter().collect();
+            for pre_evolution in species.pre_evolutions(self.data.growth.species) {
+                if let Some(info) = species.get(pre_evolution) {
+                    learnsets.extend(info.learnsets.iter());
+                }
+            }
+            for move_ in self.moves().iter().flatten() {
+                if !learnsets
+                    .iter()
+                    .any(|learnset| learns(learnset, *move_, level))
+                {
+                    problems.push(LegalityProblem::IllegalMove(move_.id()));
+                }
+            }
+            if met_level > level {
+                problems.push(LegalityProblem::MetAboveLevel {
+                    met: met_level,
+                    level,
+                });
+            }
+        }
+        // Invalid move ids are illegal whatever the species
+        let attacks = &self.data.attacks;
+        for id in [attacks.move1, attacks.move2, attacks.move3, attacks.move4] {
+            if id != 0 && Move::new(id).is_err() {
+                problems.push(LegalityProblem::IllegalMove(id));
+            }
+        }
+        let known_origin = (1..=5).contains(&origin_game) || origin_game == ORIGIN_GAMECUBE;
+        // Eggs are always given and hatched in a Poké Ball
+        let ball_ok = if met_level == 0 && (1..=5).contains(&origin_game) && !from_event {
+            ball == POKE_BALL
+        } else {
+            !known_origin || ball_available(ball, origin_game, misc.met_location)
+        };
+        if !ball_ok {
+            problems.push(LegalityProblem::UnavailableBall(ball));
+        }
+        let ribbons = misc.ribbons_and_obedience;
+        let contest_rank_too_high = (0..N_CONTEST_CATEGORIES)
+            .any(|category| (ribbons >> (category * 3)) & 0x7 > MAX_CONTEST_RANK);
+        if ribbons & UNUSED_RIBBON_BITS != 0
+            || contest_rank_too_high
+            || (self.is_egg() && ribbons & !OBEDIENCE_BIT != 0)
+        {
+            problems.push(LegalityProblem::ImpossibleRibbons);
+        }
+        if self.bad_checksum {
+            problems.push(LegalityProblem::ChecksumMismatch);
+        }
+        problems
+    }
+}
+
+#[test]
+fn test_pid_iv_match() {
+    let seed = 0x1234_5678;
+    let calls: Vec<u32> = std::iter::successors(Some(lcg(seed)), |&s| Some(lcg(s)))
+        .take(5)
+        .map(|s| s >> 16)
+        .collect();
+    let personality = (calls[1] << 16) | calls[0];
+    let ivs = |first: u32, second: u32| (first & 0x7FFF) | ((second & 0x7FFF) << 15);
+    assert!(pid_iv_match(personality, ivs(calls[2], calls[3])));
+    assert!(pid_iv_match(personality, ivs(calls[3], calls[4])));
+    assert!(pid_iv_match(personality, ivs(calls[2], calls[4])));
+    assert!(!pid_iv_match(personality, ivs(calls[4], calls[2])));
+}
+
+#[test]
+fn test_roamer_ivs_not_checked() {
+    let mut pokemon = Pokemon::default();
+    // Met at level 40 in Emerald, with IVs that don't match its personality
+    pokemon.data.misc.origins_info = (3 << 7) | 40;
+    pokemon.data.misc.ivs_eggs_and_ability = 0x3FFF_FFFF;
+    pokemon.data.growth.species = 408;
+    let problems = pokemon.legality_problems(&SpeciesTable::default());
+    assert!(!problems.contains(&LegalityProblem::PidIvMismatch));
+    pokemon.data.growth.species = 277;
+    let problems = pokemon.legality_problems(&SpeciesTable::default());
+    assert!(problems.contains(&LegalityProblem::PidIvMismatch));
+    assert!(LegalityProblem::PidIvMismatch.is_warning());
+}
+
+#[test]
+fn test_checksum_mismatch() {
+    let pokemon = Pokemon::default();
+    let table = SpeciesTable::default();
+    assert!(
+        !pokemon
+            .legality_problems(&table)
+            .contains(&LegalityProblem::ChecksumMismatch)
+    );
+    let mut raw = [0; 80];
+    pokemon.write_non_active(&mut &mut raw[..]).unwrap();
+    let read = Pokemon::read_non_active(&mut &raw[..]).unwrap();
+    assert!(
+        !read
+            .legality_problems(&table)
+            .contains(&LegalityProblem::ChecksumMismatch)
+    );
+    // The checksum follows the markings
+    raw[28] ^= 1;
+    let read = Pokemon::read_non_active(&mut &raw[..]).unwrap();
+    assert!(
+        read.legality_problems(&table)
+            .contains(&LegalityProblem::ChecksumMismatch)
+    );
+}
+
+#[test]
+fn test_ball_available() {
+    assert!(ball_available(SAFARI_BALL, 3, HOENN_SAFARI_ZONE));
+    assert!(!ball_available(SAFARI_BALL, 3, KANTO_SAFARI_ZONE));
+    assert!(ball_available(SAFARI_BALL, 4, KANTO_SAFARI_ZONE));
+    assert!(!ball_available(
+        SAFARI_BALL,
+        ORIGIN_GAMECUBE,
+        HOENN_SAFARI_ZONE
+    ));
+    assert!(ball_available(PREMIER_BALL, ORIGIN_GAMECUBE, 0));
+    assert!(!ball_available(PREMIER_BALL + 1, 1, 0));
+}
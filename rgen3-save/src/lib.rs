@@ -4,21 +4,93 @@ mod util {
     mod lower_upper;
     pub use self::lower_upper::LowerUpper;
 }
+mod berry_trees;
+mod daycare;
+mod decorations;
+mod easy_chat;
+mod eggs;
+mod evolution;
+mod flags;
+mod footer;
+mod game_state;
+mod hall_of_fame;
+mod items;
+mod language;
+mod legality;
+mod location;
+mod mail;
+mod moves;
+mod mystery_gift;
+mod options;
+mod pokeblocks;
+mod progress;
+mod recorded_battle;
+mod rival;
+mod roamer;
 mod rw;
-
+mod secret_bases;
+mod species;
+mod stats;
+mod tv;
+mod version;
+
+pub use crate::berry_trees::{
+    BerryStage, BerryTree, BerryTrees, N_BERRY_TREES, berry_growth_hours, berry_item,
+};
+pub use crate::daycare::DayCare;
+pub use crate::decorations::{CantAddDecoration, DecorationCategory, Decorations};
+pub use crate::easy_chat::{
+    EASY_CHAT_GROUP_MOVE_1, EASY_CHAT_GROUP_MOVE_2, EASY_CHAT_GROUP_POKEMON,
+    EASY_CHAT_GROUP_POKEMON_NATIONAL, EasyChatWord, InvalidPhrase, PHRASE_WORDS, Phrase,
+    PhraseKind, WordTable,
+};
+pub use crate::eggs::{CantMakeEgg, NotAnEgg};
+pub use crate::evolution::{CantEvolve, EvolutionTrigger};
+pub use crate::flags::{Flags, KnownFlag, VARS_START, Vars};
+pub use crate::footer::{FooterSectorKind, SectorStatus};
+pub use crate::hall_of_fame::{
+    CorruptHallOfFame, HALL_OF_FAME_MAX_ENTRIES, HallOfFameEntry, HallOfFameMon,
+};
+pub use crate::items::{CantHoldItem, InvalidItem, Item};
+pub use crate::language::{Language, Markings};
+pub use crate::legality::LegalityProblem;
+pub use crate::location::{Location, Warp};
+pub use crate::mail::{CantAttachMail, MAILBOX_SIZE, Mail, is_mail_item};
+pub use crate::moves::{CantSetMove, InvalidMove, Move, MoveTable, N_MOVE_SLOTS};
+pub use crate::mystery_gift::{
+    ENIGMA_BERRY_SIZE, EnigmaBerry, InvalidMysteryGift, RAM_SCRIPT_SIZE, RamScript,
+    WONDER_CARD_SIZE, WonderCard, crc16,
+};
+pub use crate::options::{
+    BattleScene, BattleStyle, ButtonMode, InvalidOptions, Options, Sound, TextSpeed,
+};
+pub use crate::pokeblocks::{N_POKEBLOCKS, Pokeblock, PokeblockCase, PokeblockColor};
+pub use crate::recorded_battle::{FrontierFacility, RecordedBattle};
+pub use crate::roamer::{Roamer, StatusCondition};
+pub use crate::secret_bases::{
+    CantImportSecretBase, N_SECRET_BASES, SECRET_BASE_DECORATIONS, SECRET_BASE_SIZE, SecretBase,
+    SecretBaseMon,
+};
+pub use crate::species::{
+    Evolution, EvolutionMethod, GrowthRate, Learnset, Learnsets, SpeciesInfo, SpeciesTable,
+};
+pub use crate::stats::{GameStat, GameStats};
+pub use crate::tv::{TvInterview, TvInterviewKind};
+pub use crate::version::{Confidence, DetectedVersion, GameVersion};
+
+use crate::footer::FooterSector;
 use crate::util::LowerUpper;
 use std::error::Error;
 use std::fs::File;
 use std::path::Path;
 use std::{fmt, io};
 
-const UNKNOWN_SAVE_FOOTER_SIZE: usize = 16384;
-
 /// Pokémon Gen3 save data.
 pub struct Save {
     blocks: [SaveBlock; 2],
-    unknown: [u8; UNKNOWN_SAVE_FOOTER_SIZE],
+    footer: Vec<FooterSector>,
     most_recent_index: usize,
+    version: DetectedVersion,
 }
 
 impl fmt::Debug for Save {
@@ -33,12 +105,24 @@ impl Save {
         let mut file = File::open(path)?;
         Save::read(&mut file)
     }
+    /// Load the save data from a file at the provided path, skipping version detection.
+    pub fn load_from_file_as<P: AsRef<Path>>(
+        path: P,
+        version: GameVersion,
+    ) -> Result<Self, Box<dyn Error>> {
+        let mut file = File::open(path)?;
+        Save::read_as(&mut file, version)
+    }
     /// Save the save data to a file at the provided path.
     pub fn save_to_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), io::Error> {
         let mut file = File::create(path)?;
         self.write(&mut file)
     }
-    pub fn sections_mut(&mut self) -> SaveSectionsMut {
+    /// The game this save was detected to be from, or forced to be when loading.
+    pub fn game_version(&self) -> DetectedVersion {
+        self.version
+    }
+    pub fn sections_mut(&mut self) -> SaveSectionsMut<'_> {
         let block = &mut self.blocks[self.most_recent_index];
         let sections = &mut block.sections[..];
         let (team_items_sec, trainer_sec) = if block.team_and_items_index > block.trainer_info_index
@@ -65,7 +149,7 @@ impl Save {
             pc_boxes: &mut block.pokemon_storage.boxes,
         }
     }
-    pub fn sections(&self) -> SaveSections {
+    pub fn sections(&self) -> SaveSections<'_> {
         let block = &self.blocks[self.most_recent_index];
         let sections = &block.sections[..];
         let (team_items_sec, trainer_sec) = (
@@ -88,6 +172,47 @@ impl Save {
             pc_boxes: &block.pokemon_storage.boxes,
         }
     }
+    /// Change the trainer's public and secret ID.
+    ///
+    /// If `update_owned` is true, every party and PC Pokémon whose original trainer is the
+    /// player (same name and old ID) gets its `ot_id` updated as well, so it doesn't turn into
+    /// a traded Pokémon that may disobey. Their data is encrypted with the new key
+    /// (`ot_id ^ personality`) when the save is written.
+    ///
+    /// Note that shininess depends on the OT ID, so it may change for updated Pokémon.
+    ///
+    /// Returns the number of Pokémon that were updated.
+    pub fn change_trainer_id(
+        &mut self,
+        public_id: u16,
+        secret_id: u16,
+        update_owned: bool,
+    ) -> usize {
+        let SaveSectionsMut {
+            trainer,
+            team,
+            pc_boxes,
+        } = self.sections_mut();
+        let old_id = trainer.full_id();
+        trainer.set_public_id(public_id);
+        trainer.set_secret_id(secret_id);
+        if !update_owned {
+            return 0;
+        }
+        let new_id = trainer.full_id();
+        let name = trainer.name;
+        let boxed = pc_boxes
+            .iter_mut()
+            .flat_map(|b| b.slots.iter_mut().flatten());
+        let mut n_updated = 0;
+        for pokemon in team.iter_mut().chain(boxed) {
+            if pokemon.ot_id == old_id && pokemon.ot_name.same_as(&name) {
+                pokemon.ot_id = new_id;
+                n_updated += 1;
+            }
+        }
+        n_updated
+    }
 }
 
 pub struct SaveSectionsMut<'a> {
@@ -110,8 +235,10 @@ struct SaveBlock {
     // Does not exist yet, meaning the game has only been saved once, and this
     // block hasn't been written over yet.
     nonexistent: bool,
+    layout_confidence: Confidence,
     pokemon_storage: PokemonStorage,
     box_indexes: [usize; N_BOXES],
+    game_state_indexes: [usize; N_GAME_STATE_SECTIONS],
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -124,6 +251,7 @@ enum SectionData {
     TrainerInfo(TrainerInfo),
     TeamAndItems(TeamAndItems),
     PcBuffer(PcBuffer),
+    GameState(GameStateBuffer),
 }
 
 impl fmt::Debug for SectionData {
@@ -133,6 +261,7 @@ impl fmt::Debug for SectionData {
             SectionData::TrainerInfo(ref data) => data.fmt(f),
             SectionData::TeamAndItems(ref data) => data.fmt(f),
             SectionData::PcBuffer(ref data) => data.fmt(f),
+            SectionData::GameState(ref data) => data.fmt(f),
         }
     }
 }
@@ -146,9 +275,10 @@ struct Section {
 
 const DATA_SIZE: i64 = 0xFF4;
 
+/// Gender of the player character.
 #[repr(u8)]
-#[derive(Clone, Copy, Debug)]
-enum Gender {
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Gender {
     Male = 0,
     Female = 1,
 }
@@ -163,24 +293,73 @@ impl fmt::Display for Gender {
     }
 }
 
-#[derive(Debug)]
-struct Time {
+/// Amount of time played, as shown on the trainer card.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Time {
     hours: u16,
     minutes: u8,
     seconds: u8,
     frames: u8,
 }
 
-const TRAINER_INFO_UNKNOWN_3_SIZE: usize = 0x00AC - (0x0013 + 3);
+/// Error returned when trying to create a `Time` with an out of range field.
+#[derive(Debug)]
+pub struct InvalidTime;
+
+impl Time {
+    /// The game stops counting at 999:59:59.
+    pub const MAX_HOURS: u16 = 999;
+    /// The game counts 60 frames per second.
+    pub const FRAMES_PER_SECOND: u8 = 60;
+    /// Create a new `Time`, validating that every field is within the range the game uses.
+    pub fn new(hours: u16, minutes: u8, seconds: u8, frames: u8) -> Result<Self, InvalidTime> {
+        if hours > Self::MAX_HOURS
+            || minutes >= 60
+            || seconds >= 60
+            || frames >= Self::FRAMES_PER_SECOND
+        {
+            return Err(InvalidTime);
+        }
+        Ok(Time {
+            hours,
+            minutes,
+            seconds,
+            frames,
+        })
+    }
+    pub fn hours(&self) -> u16 {
+        self.hours
+    }
+    pub fn minutes(&self) -> u8 {
+        self.minutes
+    }
+    pub fn seconds(&self) -> u8 {
+        self.seconds
+    }
+    pub fn frames(&self) -> u8 {
+        self.frames
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{:02}:{:02}", self.hours, self.minutes, self.seconds)
+    }
+}
+
+const TRAINER_INFO_UNKNOWN_3_OFFSET: usize = 0x0013 + 3;
+const TRAINER_INFO_UNKNOWN_3_SIZE: usize = 0x00AC - TRAINER_INFO_UNKNOWN_3_OFFSET;
 const RS_EM_PLAYERINFO_TRAILING_DATA_SIZE: usize = DATA_SIZE as usize - (0x0AC + 4);
 const FRLG_PLAYERINFO_UNKNOWN_CHUNK_SIZE: usize = 0x0AF8 - (0x00AC + 4);
 const FRLG_PLAYERINFO_TRAILING_DATA_SIZE: usize = DATA_SIZE as usize - (0x0AF8 + 4);
 
 enum Game {
     RubyOrSapphire {
+        game_code: u32,
         trailing_data: [u8; RS_EM_PLAYERINFO_TRAILING_DATA_SIZE],
     },
     FireredOrLeafgreen {
+        game_code: u32,
         unknown: [u8; FRLG_PLAYERINFO_UNKNOWN_CHUNK_SIZE],
         security_key: u32,
         trailing_data: [u8; FRLG_PLAYERINFO_TRAILING_DATA_SIZE],
@@ -191,7 +370,7 @@ enum Game {
     },
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 enum GameType {
     RubyOrSapphire,
     FireredOrLeafgreen,
@@ -241,6 +420,65 @@ impl TrainerInfo {
     pub fn full_id(&self) -> u32 {
         u32::merge(self.public_id, self.secret_id)
     }
+    pub fn gender(&self) -> Gender {
+        self.gender
+    }
+    pub fn set_gender(&mut self, gender: Gender) {
+        self.gender = gender;
+    }
+    /// The trainer ID shown on the trainer card.
+    pub fn public_id(&self) -> u16 {
+        self.public_id
+    }
+    /// Changes the trainer ID shown on the trainer card.
+    ///
+    /// Pokémon caught by this trainer are not updated, see `Save::change_trainer_id`.
+    pub fn set_public_id(&mut self, id: u16) {
+        self.public_id = id;
+    }
+    /// The hidden half of the trainer ID, used for shininess and Pokémon encryption.
+    pub fn secret_id(&self) -> u16 {
+        self.secret_id
+    }
+    /// Changes the hidden half of the trainer ID.
+    ///
+    /// Pokémon caught by this trainer are not updated, see `Save::change_trainer_id`.
+    pub fn set_secret_id(&mut self, id: u16) {
+        self.secret_id = id;
+    }
+    pub fn time_played(&self) -> Time {
+        self.time_played
+    }
+    pub fn set_time_played(&mut self, time: Time) {
+        self.time_played = time;
+    }
+    /// A byte of the data not modelled by `TrainerInfo` yet, by its offset in the section.
+    pub(crate) fn unknown_byte(&self, offset: usize) -> u8 {
+        self.unknown_3[offset - TRAINER_INFO_UNKNOWN_3_OFFSET]
+    }
+    /// The key some values are xored with. Ruby/Sapphire doesn't encrypt anything, so it's 0.
+    pub(crate) fn security_key(&self) -> u32 {
+        match self.game {
+            Game::RubyOrSapphire { .. } => 0,
+            Game::FireredOrLeafgreen { security_key, .. } | Game::Emerald { security_key, .. } => {
+                security_key
+            }
+        }
+    }
+    /// Decode the options menu settings.
+    pub fn options(&self) -> Result<Options, InvalidOptions> {
+        Options::decode(&self.options_data)
+    }
+    /// Change the options menu settings.
+    ///
+    /// Fails if the frame type is not available in this game.
+    pub fn set_options(&mut self, options: &Options) -> Result<(), InvalidOptions> {
+        if options.frame_type >= options::n_frame_types(GameType::from(&self.game)) {
+            return Err(InvalidOptions);
+        }
+        options.encode(&mut self.options_data);
+        Ok(())
+    }
 }
 
 impl fmt::Debug for TrainerInfo {
@@ -322,8 +560,20 @@ debug_impl!(PokemonNick);
 debug_impl!(TrainerName);
 debug_impl!(BoxName);
 
+impl TrainerName {
+    /// Whether both names are the same. What follows the terminator is ignored, as the game
+    /// doesn't always clear it.
+    pub(crate) fn same_as(&self, other: &TrainerName) -> bool {
+        fn text(name: &[u8]) -> &[u8] {
+            let len = name.iter().position(|&c| c == 0xFF).unwrap_or(name.len());
+            &name[..len]
+        }
+        text(&self.0) == text(&other.0)
+    }
+}
+
 /// A Pokemon.
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 #[allow(missing_docs)]
 pub struct Pokemon {
     pub personality: u32,
@@ -333,6 +583,8 @@ pub struct Pokemon {
     pub ot_name: TrainerName,
     markings: u8,
     _checksum: u16,
+    /// Whether `_checksum` didn't match the data when the Pokémon was read from a save.
+    bad_checksum: bool,
     unknown_1: u16,
     pub data: PokemonData,
     pub active_data: Option<PokemonActiveData>,
@@ -341,6 +593,10 @@ pub struct Pokemon {
 #[derive(Debug)]
 pub struct InvalidSpecies;
 
+/// Error returned when accessing data that doesn't exist in the game the save is from.
+#[derive(Debug)]
+pub struct UnsupportedGame;
+
 impl Pokemon {
     pub fn set_species(&mut self, num: u16) -> Result<(), InvalidSpecies> {
         match num {
@@ -354,11 +610,12 @@ impl Pokemon {
 }
 
 /// "Active" data that is not stored in the PC boxes.
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct PokemonActiveData {
     status_condition: u32,
     pub level: u8,
-    pokerus_remaining: u8,
+    /// Which of the party mail slots holds the mail this Pokémon carries, if it holds mail.
+    mail_id: u8,
     pub current_hp: u16,
     pub total_hp: u16,
     pub attack: u16,
@@ -368,7 +625,7 @@ pub struct PokemonActiveData {
     pub sp_defense: u16,
 }
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct PokemonData {
     pub growth: PokemonGrowth,
     pub attacks: PokemonAttacks,
@@ -376,7 +633,7 @@ pub struct PokemonData {
     misc: PokemonMisc,
 }
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct PokemonGrowth {
     pub species: u16,
     item_held: u16,
@@ -386,7 +643,7 @@ pub struct PokemonGrowth {
     unknown: u16,
 }
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct PokemonAttacks {
     pub move1: u16,
     pub move2: u16,
@@ -398,7 +655,7 @@ pub struct PokemonAttacks {
     pub pp4: u8,
 }
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct PokemonEvsAndCondition {
     pub hp: u8,
     pub attack: u8,
@@ -414,7 +671,7 @@ pub struct PokemonEvsAndCondition {
     pub feel: u8,
 }
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 struct PokemonMisc {
     pokerus_status: u8,
     met_location: u8,
@@ -423,6 +680,27 @@ struct PokemonMisc {
     ribbons_and_obedience: u32,
 }
 
+impl PokemonMisc {
+    /// The game code of the game the Pokémon was caught in.
+    fn origin_game(&self) -> u16 {
+        (self.origins_info >> 7) & 0xF
+    }
+}
+
+/// Sections 2 to 4, which only hold game state, like flags and variables.
+const N_GAME_STATE_SECTIONS: usize = 3;
+
+struct GameStateBuffer {
+    data: [u8; DATA_SIZE as usize],
+    index: usize,
+}
+
+impl fmt::Debug for GameStateBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Game state {}", self.index)
+    }
+}
+
 struct PcBuffer {
     data: [u8; DATA_SIZE as usize],
     index: usize,
@@ -460,3 +738,65 @@ pub struct PokeBox {
     wallpaper: u8,
     pub slots: [Option<Pokemon>; N_POKEMON_PER_BOX],
 }
+
+#[test]
+fn test_change_trainer_id() {
+    use std::io::Cursor;
+    let mut save = rw::blank_save(GameVersion::Emerald);
+    let SaveSectionsMut {
+        trainer,
+        team,
+        pc_boxes,
+    } = save.sections_mut();
+    rgen3_string::encode_string("ASH", &mut trainer.name.0);
+    trainer.set_public_id(1);
+    let old_id = trainer.full_id();
+    let mut owned = Pokemon {
+        personality: 0x1234_5678,
+        ot_id: old_id,
+        active_data: Some(PokemonActiveData::default()),
+        ..Default::default()
+    };
+    owned.data.growth.species = 25;
+    // The padding after the terminator differs from the trainer's name
+    owned.ot_name.0 = [0xBB, 0xCD, 0xC2, 0xFF, 0, 0, 0];
+    let traded = Pokemon {
+        ot_id: 0x9999_9999,
+        ..owned.clone()
+    };
+    team.extend([owned.clone(), traded]);
+    pc_boxes[3].slots[7] = Some(owned);
+    assert_eq!(save.change_trainer_id(2, 3, true), 2);
+    let mut data = Vec::new();
+    save.write(&mut data).unwrap();
+    let save = Save::read_as(&mut Cursor::new(data), GameVersion::Emerald).unwrap();
+    let SaveSections {
+        trainer,
+        team,
+        pc_boxes,
+    } = save.sections();
+    let new_id = trainer.full_id();
+    assert_eq!(new_id, 0x0003_0002);
+    let boxed = pc_boxes[3].slots[7].as_ref().unwrap();
+    for pokemon in [&team[0], boxed] {
+        assert_eq!(pokemon.ot_id, new_id);
+        assert_eq!(pokemon.data.growth.species, 25);
+        assert!(!pokemon.bad_checksum);
+    }
+    assert_eq!(team[1].ot_id, 0x9999_9999);
+    assert_eq!(team[1].data.growth.species, 25);
+    assert!(!team[1].bad_checksum);
+}
+
+#[test]
+fn test_time_bounds() {
+    let time = Time::new(999, 59, 59, 59).unwrap();
+    assert_eq!(
+        (time.hours(), time.minutes(), time.seconds(), time.frames()),
+        (999, 59, 59, 59)
+    );
+    assert!(Time::new(1000, 0, 0, 0).is_err());
+    assert!(Time::new(0, 60, 0, 0).is_err());
+    assert!(Time::new(0, 0, 60, 0).is_err());
+    assert!(Time::new(0, 0, 0, 60).is_err());
+}
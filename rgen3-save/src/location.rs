@@ -0,0 +1,135 @@
+//! Player position and warp data.
+//!
+//! These are at the very start of the game state, with the same layout in every game.
+
+use crate::Save;
+use byteorder::{ByteOrder, LittleEndian as LE};
+
+const WARP_SIZE: usize = 8;
+/// Size of the position and the five warps.
+const LOCATION_SIZE: usize = 4 + 5 * WARP_SIZE;
+
+/// A position on a map, as used for warps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Warp {
+    pub map_group: i8,
+    pub map_num: i8,
+    /// The warp (door, stairs, ...) to arrive at, or -1 to use the coordinates.
+    pub warp_id: i8,
+    pub x: i16,
+    pub y: i16,
+}
+
+impl Warp {
+    fn read(data: &[u8]) -> Self {
+        Warp {
+            map_group: data[0] as i8,
+            map_num: data[1] as i8,
+            warp_id: data[2] as i8,
+            x: LE::read_i16(&data[4..]),
+            y: LE::read_i16(&data[6..]),
+        }
+    }
+    // The padding byte at offset 3 is left untouched
+    fn write(&self, data: &mut [u8]) {
+        data[0] = self.map_group as u8;
+        data[1] = self.map_num as u8;
+        data[2] = self.warp_id as u8;
+        LE::write_i16(&mut data[4..], self.x);
+        LE::write_i16(&mut data[6..], self.y);
+    }
+}
+
+/// Where the player is, and where the game sends them in various situations.
+///
+/// Get it with `Save::location`, and write it back with `Save::set_location`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Location {
+    /// Coordinates of the player on the current map.
+    pub x: i16,
+    pub y: i16,
+    /// The current map.
+    pub map: Warp,
+    /// Used instead of `map` when continuing the game in some special cases, like after a
+    /// link battle.
+    pub continue_game: Warp,
+    /// Where warps with a dynamic destination lead to, like the exit of a building.
+    pub dynamic: Warp,
+    /// The Pokémon Center (or home) the player is sent to after blacking out.
+    pub last_heal: Warp,
+    /// Where Dig and Escape Rope lead to.
+    pub escape: Warp,
+}
+
+impl Location {
+    /// Move the player to their last heal location, to get them unstuck.
+    pub fn teleport_to_last_heal(&mut self) {
+        self.map = Warp {
+            warp_id: -1,
+            ..self.last_heal
+        };
+        self.x = self.last_heal.x;
+        self.y = self.last_heal.y;
+    }
+}
+
+impl Save {
+    pub fn location(&self) -> Location {
+        let mut raw = [0; LOCATION_SIZE];
+        self.read_game_state(0, &mut raw);
+        let warp = |n: usize| Warp::read(&raw[4 + n * WARP_SIZE..]);
+        Location {
+            x: LE::read_i16(&raw[0..]),
+            y: LE::read_i16(&raw[2..]),
+            map: warp(0),
+            continue_game: warp(1),
+            dynamic: warp(2),
+            last_heal: warp(3),
+            escape: warp(4),
+        }
+    }
+    pub fn set_location(&mut self, location: &Location) {
+        let mut raw = [0; LOCATION_SIZE];
+        self.read_game_state(0, &mut raw);
+        LE::write_i16(&mut raw[0..], location.x);
+        LE::write_i16(&mut raw[2..], location.y);
+        let warps = [
+            location.map,
+            location.continue_game,
+            location.dynamic,
+            location.last_heal,
+            location.escape,
+        ];
+        for (n, warp) in warps.iter().enumerate() {
+            warp.write(&mut raw[4 + n * WARP_SIZE..]);
+        }
+        self.write_game_state(0, &raw);
+    }
+}
+
+#[test]
+fn test_location_roundtrip() {
+    use crate::GameVersion;
+    let mut save = crate::rw::blank_save(GameVersion::Emerald);
+    let mut location = save.location();
+    location.last_heal = Warp {
+        map_group: 0,
+        map_num: 4,
+        warp_id: 1,
+        x: 10,
+        y: -2,
+    };
+    location.teleport_to_last_heal();
+    assert_eq!((location.x, location.y), (10, -2));
+    assert_eq!(location.map.warp_id, -1);
+    assert_eq!(location.map.map_num, 4);
+    save.set_location(&location);
+    assert_eq!(save.location(), location);
+    let mut raw = [0; LOCATION_SIZE];
+    save.read_game_state(0, &mut raw);
+    // The last heal location is the fourth warp
+    assert_eq!(
+        raw[4 + 3 * WARP_SIZE..][..8],
+        [0, 4, 1, 0, 10, 0, 0xFE, 0xFF]
+    );
+}
@@ -0,0 +1,232 @@
+//! Mail, held by party Pokémon or kept in the PC mailbox.
+//!
+//! The save has 16 mail slots: one for each party Pokémon, then the 10 of the PC mailbox.
+//! A Pokémon holding mail refers to its slot through `PokemonActiveData::mail_id`.
+
+use crate::easy_chat::{EasyChatWord, WordTable};
+use crate::{GameType, PokemonActiveData, Save, TRAINER_NAME_LEN, TrainerName};
+use byteorder::{ByteOrder, LittleEndian as LE};
+
+const MAIL_SIZE: usize = 36;
+const MAIL_WORDS: usize = 9;
+const PARTY_MAIL_SLOTS: usize = 6;
+pub const MAILBOX_SIZE: usize = 10;
+const N_MAIL_SLOTS: usize = PARTY_MAIL_SLOTS + MAILBOX_SIZE;
+/// `mail_id` of a Pokémon that holds no mail.
+const MAIL_NONE: u8 = 0xFF;
+
+const NAME_OFFSET: usize = 0x12;
+const TRAINER_ID_OFFSET: usize = 0x1A;
+const SPECIES_OFFSET: usize = 0x1E;
+const ITEM_OFFSET: usize = 0x20;
+
+fn mail_offset(game: GameType) -> usize {
+    match game {
+        GameType::RubyOrSapphire => 0x2B4C,
+        GameType::Emerald => 0x2BE0,
+        GameType::FireredOrLeafgreen => 0x2CD0,
+    }
+}
+
+/// Whether `item` is one of the mail items.
+pub fn is_mail_item(item: u16) -> bool {
+    (121..=132).contains(&item)
+}
+
+/// A mail message.
+#[derive(Clone, Copy, Debug)]
+pub struct Mail {
+    pub words: [EasyChatWord; MAIL_WORDS],
+    /// Who wrote the mail.
+    pub author: TrainerName,
+    pub author_id: u32,
+    /// The species of the Pokémon that held the mail when it was written.
+    pub species: u16,
+    /// The mail item, which decides the stationery.
+    pub item: u16,
+}
+
+/// Error returned when mail can't be given to a Pokémon.
+#[derive(Debug)]
+pub struct CantAttachMail;
+
+impl Mail {
+    fn read(data: &[u8]) -> Option<Self> {
+        let item = LE::read_u16(&data[ITEM_OFFSET..]);
+        if item == 0 {
+            return None;
+        }
+        let mut words = [EasyChatWord::EMPTY; MAIL_WORDS];
+        for (i, word) in words.iter_mut().enumerate() {
+            *word = EasyChatWord(LE::read_u16(&data[i * 2..]));
+        }
+        let mut author = TrainerName::default();
+        author
+            .0
+            .copy_from_slice(&data[NAME_OFFSET..NAME_OFFSET + TRAINER_NAME_LEN]);
+        Some(Mail {
+            words,
+            author,
+            author_id: LE::read_u32(&data[TRAINER_ID_OFFSET..]),
+            species: LE::read_u16(&data[SPECIES_OFFSET..]),
+            item,
+        })
+    }
+    fn write(&self, data: &mut [u8]) {
+        for (i, word) in self.words.iter().enumerate() {
+            LE::write_u16(&mut data[i * 2..], word.0);
+        }
+        data[NAME_OFFSET..NAME_OFFSET + TRAINER_NAME_LEN].copy_from_slice(&self.author.0);
+        data[NAME_OFFSET + TRAINER_NAME_LEN] = 0xFF;
+        LE::write_u32(&mut data[TRAINER_ID_OFFSET..], self.author_id);
+        LE::write_u16(&mut data[SPECIES_OFFSET..], self.species);
+        LE::write_u16(&mut data[ITEM_OFFSET..], self.item);
+    }
+    /// An empty slot, as the game clears it.
+    fn write_empty(data: &mut [u8]) {
+        for b in &mut data[..NAME_OFFSET + TRAINER_NAME_LEN + 1] {
+            *b = 0xFF;
+        }
+        LE::write_u32(&mut data[TRAINER_ID_OFFSET..], 0);
+        LE::write_u16(&mut data[SPECIES_OFFSET..], 1);
+        LE::write_u16(&mut data[ITEM_OFFSET..], 0);
+    }
+    /// The message, with the words looked up in `table`.
+    pub fn text(&self, table: &WordTable) -> String {
+        table.decode(&self.words)
+    }
+}
+
+impl PokemonActiveData {
+    /// The mail slot of the mail this Pokémon holds, if it holds mail.
+    ///
+    /// Slots 0 to 5 are the party slots, and the mailbox follows them. This is only
+    /// meaningful when the held item is a mail item, see `Save::held_mail`.
+    pub fn mail_id(&self) -> Option<usize> {
+        Some(usize::from(self.mail_id)).filter(|&id| id < N_MAIL_SLOTS)
+    }
+}
+
+impl Save {
+    fn mail_slot(&self, slot: usize) -> Option<Mail> {
+        let mut data = [0; MAIL_SIZE];
+        self.read_game_state(mail_offset(self.game_type()) + slot * MAIL_SIZE, &mut data);
+        Mail::read(&data)
+    }
+    fn set_mail_slot(&mut self, slot: usize, mail: Option<&Mail>) {
+        let offset = mail_offset(self.game_type()) + slot * MAIL_SIZE;
+        let mut data = [0; MAIL_SIZE];
+        self.read_game_state(offset, &mut data);
+        match mail {
+            Some(mail) => mail.write(&mut data),
+            None => Mail::write_empty(&mut data),
+        }
+        self.write_game_state(offset, &data);
+    }
+    /// The mail kept in the PC mailbox. Empty slots are `None`.
+    pub fn mailbox(&self) -> Vec<Option<Mail>> {
+        (PARTY_MAIL_SLOTS..N_MAIL_SLOTS)
+            .map(|slot| self.mail_slot(slot))
+            .collect()
+    }
+    /// Overwrite the mailbox slot at `index`, or empty it with `None`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not less than `MAILBOX_SIZE`.
+    pub fn set_mailbox_slot(&mut self, index: usize, mail: Option<&Mail>) {
+        assert!(index < MAILBOX_SIZE, "Invalid mailbox slot: {}", index);
+        self.set_mail_slot(PARTY_MAIL_SLOTS + index, mail);
+    }
+    /// The mail held by the party Pokémon at `party_index`, if any.
+    pub fn held_mail(&self, party_index: usize) -> Option<Mail> {
+        let pokemon = self.sections().team.get(party_index)?;
+        let mail_id = pokemon.active_data.as_ref()?.mail_id()?;
+        if !is_mail_item(pokemon.data.growth.item_held) {
+            return None;
+        }
+        self.mail_slot(mail_id)
+    }
+    /// Give `mail` to the party Pokémon at `party_index`, replacing its held item and any mail
+    /// it held.
+    ///
+    /// Fails if there is no Pokémon there or it is an egg, `mail.item` is not a mail item, or
+    /// every party mail slot is taken.
+    pub fn attach_mail(&mut self, party_index: usize, mail: &Mail) -> Result<(), CantAttachMail> {
+        if !is_mail_item(mail.item) {
+            return Err(CantAttachMail);
+        }
+        let current = {
+            let pokemon = self
+                .sections()
+                .team
+                .get(party_index)
+                .filter(|pokemon| !pokemon.is_egg())
+                .ok_or(CantAttachMail)?;
+            let active_data = pokemon.active_data.as_ref().ok_or(CantAttachMail)?;
+            Some(usize::from(active_data.mail_id))
+                .filter(|&id| id < PARTY_MAIL_SLOTS && is_mail_item(pokemon.data.growth.item_held))
+        };
+        let slot = current
+            .or_else(|| (0..PARTY_MAIL_SLOTS).find(|&slot| self.mail_slot(slot).is_none()))
+            .ok_or(CantAttachMail)?;
+        self.set_mail_slot(slot, Some(mail));
+        let pokemon = &mut self.sections_mut().team[party_index];
+        pokemon.data.growth.item_held = mail.item;
+        if let Some(ref mut active_data) = pokemon.active_data {
+            active_data.mail_id = slot as u8;
+        }
+        Ok(())
+    }
+    /// Take the mail away from the party Pokémon at `party_index`, leaving it without a held
+    /// item. Returns the mail, if it held any.
+    pub fn take_mail(&mut self, party_index: usize) -> Option<Mail> {
+        let mail = self.held_mail(party_index)?;
+        let slot = self.sections().team[party_index]
+            .active_data
+            .as_ref()?
+            .mail_id()?;
+        self.set_mail_slot(slot, None);
+        let pokemon = &mut self.sections_mut().team[party_index];
+        pokemon.data.growth.item_held = 0;
+        if let Some(ref mut active_data) = pokemon.active_data {
+            active_data.mail_id = MAIL_NONE;
+        }
+        Some(mail)
+    }
+}
+
+#[test]
+fn test_attach_mail() {
+    use crate::{GameVersion, Pokemon};
+    let mut save = crate::rw::blank_save(GameVersion::Emerald);
+    let pokemon = Pokemon {
+        active_data: Some(PokemonActiveData {
+            mail_id: MAIL_NONE,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    save.sections_mut().team.extend([pokemon.clone(), pokemon]);
+    let mut mail = Mail {
+        words: [EasyChatWord::EMPTY; MAIL_WORDS],
+        author: TrainerName::default(),
+        author_id: 0x1234,
+        species: 25,
+        item: 1,
+    };
+    assert!(save.attach_mail(0, &mail).is_err());
+    mail.item = 121;
+    // The first slot is taken by mail that isn't held anymore
+    save.set_mail_slot(0, Some(&mail));
+    save.attach_mail(1, &mail).unwrap();
+    let team = save.sections().team;
+    assert_eq!(team[1].active_data.as_ref().unwrap().mail_id(), Some(1));
+    assert_eq!(team[0].active_data.as_ref().unwrap().mail_id(), None);
+    assert_eq!(save.held_mail(1).unwrap().author_id, 0x1234);
+    assert!(save.held_mail(0).is_none());
+    assert_eq!(save.take_mail(1).unwrap().species, 25);
+    assert!(save.held_mail(1).is_none());
+    assert!(save.mail_slot(1).is_none());
+    assert_eq!(save.sections().team[1].data.growth.item_held, 0);
+}
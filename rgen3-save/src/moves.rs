@@ -0,0 +1,568 @@
+//! The moves of a Pokémon.
+//!
+//! Like the species data, move data is not part of the save, and has to be provided by the
+//! user. Only the move names are built in.
+
+use crate::species::{Learnset, SpeciesTable};
+use crate::{GameVersion, Pokemon};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The highest move id, Psycho Boost.
+pub(crate) const LAST_MOVE: u16 = 354;
+pub const N_MOVE_SLOTS: usize = 4;
+
+/// The names of the moves, as the games show them, by id from 1.
+const MOVE_NAMES: [&str; LAST_MOVE as usize] = [
+    "POUND",
+    "KARATE CHOP",
+    "DOUBLESLAP",
+    "COMET PUNCH",
+    "MEGA PUNCH",
+    "PAY DAY",
+    "FIRE PUNCH",
+    "ICE PUNCH",
+    "THUNDERPUNCH",
+    "SCRATCH",
+    "VICEGRIP",
+    "GUILLOTINE",
+    "RAZOR WIND",
+    "SWORDS DANCE",
+    "CUT",
+    "GUST",
+    "WING ATTACK",
+    "WHIRLWIND",
+    "FLY",
+    "BIND",
+    "SLAM",
+    "VINE WHIP",
+    "STOMP",
+    "DOUBLE KICK",
+    "MEGA KICK",
+    "JUMP KICK",
+    "ROLLING KICK",
+    "SAND-ATTACK",
+    "HEADBUTT",
+    "HORN ATTACK",
+    "FURY ATTACK",
+    "HORN DRILL",
+    "TACKLE",
+    "BODY SLAM",
+    "WRAP",
+    "TAKE DOWN",
+    "THRASH",
+    "DOUBLE-EDGE",
+    "TAIL WHIP",
+    "POISON STING",
+    "TWINEEDLE",
+    "PIN MISSILE",
+    "LEER",
+    "BITE",
+    "GROWL",
+    "ROAR",
+    "SING",
+    "SUPERSONIC",
+    "SONICBOOM",
+    "DISABLE",
+    "ACID",
+    "EMBER",
+    "FLAMETHROWER",
+    "MIST",
+    "WATER GUN",
+    "HYDRO PUMP",
+    "SURF",
+    "ICE BEAM",
+    "BLIZZARD",
+    "PSYBEAM",
+    "BUBBLEBEAM",
+    "AURORA BEAM",
+    "HYPER BEAM",
+    "PECK",
+    "DRILL PECK",
+    "SUBMISSION",
+    "LOW KICK",
+    "COUNTER",
+    "SEISMIC TOSS",
+    "STRENGTH",
+    "ABSORB",
+    "MEGA DRAIN",
+    "LEECH SEED",
+    "GROWTH",
+    "RAZOR LEAF",
+    "SOLARBEAM",
+    "POISONPOWDER",
+    "STUN SPORE",
+    "SLEEP POWDER",
+    "PETAL DANCE",
+    "STRING SHOT",
+    "DRAGON RAGE",
+    "FIRE SPIN",
+    "THUNDERSHOCK",
+    "THUNDERBOLT",
+    "THUNDER WAVE",
+    "THUNDER",
+    "ROCK THROW",
+    "EARTHQUAKE",
+    "FISSURE",
+    "DIG",
+    "TOXIC",
+    "CONFUSION",
+    "PSYCHIC",
+    "HYPNOSIS",
+    "MEDITATE",
+    "AGILITY",
+    "QUICK ATTACK",
+    "RAGE",
+    "TELEPORT",
+    "NIGHT SHADE",
+    "MIMIC",
+    "SCREECH",
+    "DOUBLE TEAM",
+    "RECOVER",
+    "HARDEN",
+    "MINIMIZE",
+    "SMOKESCREEN",
+    "CONFUSE RAY",
+    "WITHDRAW",
+    "DEFENSE CURL",
+    "BARRIER",
+    "LIGHT SCREEN",
+    "HAZE",
+    "REFLECT",
+    "FOCUS ENERGY",
+    "BIDE",
+    "METRONOME",
+    "MIRROR MOVE",
+    "SELFDESTRUCT",
+    "EGG BOMB",
+    "LICK",
+    "SMOG",
+    "SLUDGE",
+    "BONE CLUB",
+    "FIRE BLAST",
+    "WATERFALL",
+    "CLAMP",
+    "SWIFT",
+    "SKULL BASH",
+    "SPIKE CANNON",
+    "CONSTRICT",
+    "AMNESIA",
+    "KINESIS",
+    "SOFTBOILED",
+    "HI JUMP KICK",
+    "GLARE",
+    "DREAM EATER",
+    "POISON GAS",
+    "BARRAGE",
+    "LEECH LIFE",
+    "LOVELY KISS",
+    "SKY ATTACK",
+    "TRANSFORM",
+    "BUBBLE",
+    "DIZZY PUNCH",
+    "SPORE",
+    "FLASH",
+    "PSYWAVE",
+    "SPLASH",
+    "ACID ARMOR",
+    "CRABHAMMER",
+    "EXPLOSION",
+    "FURY SWIPES",
+    "BONEMERANG",
+    "REST",
+    "ROCK SLIDE",
+    "HYPER FANG",
+    "SHARPEN",
+    "CONVERSION",
+    "TRI ATTACK",
+    "SUPER FANG",
+    "SLASH",
+    "SUBSTITUTE",
+    "STRUGGLE",
+    "SKETCH",
+    "TRIPLE KICK",
+    "THIEF",
+    "SPIDER WEB",
+    "MIND READER",
+    "NIGHTMARE",
+    "FLAME WHEEL",
+    "SNORE",
+    "CURSE",
+    "FLAIL",
+    "CONVERSION 2",
+    "AEROBLAST",
+    "COTTON SPORE",
+    "REVERSAL",
+    "SPITE",
+    "POWDER SNOW",
+    "PROTECT",
+    "MACH PUNCH",
+    "SCARY FACE",
+    "FAINT ATTACK",
+    "SWEET KISS",
+    "BELLY DRUM",
+    "SLUDGE BOMB",
+    "MUD-SLAP",
+    "OCTAZOOKA",
+    "SPIKES",
+    "ZAP CANNON",
+    "FORESIGHT",
+    "DESTINY BOND",
+    "PERISH SONG",
+    "ICY WIND",
+    "DETECT",
+    "BONE RUSH",
+    "LOCK-ON",
+    "OUTRAGE",
+    "SANDSTORM",
+    "GIGA DRAIN",
+    "ENDURE",
+    "CHARM",
+    "ROLLOUT",
+    "FALSE SWIPE",
+    "SWAGGER",
+    "MILK DRINK",
+    "SPARK",
+    "FURY CUTTER",
+    "STEEL WING",
+    "MEAN LOOK",
+    "ATTRACT",
+    "SLEEP TALK",
+    "HEAL BELL",
+    "RETURN",
+    "PRESENT",
+    "FRUSTRATION",
+    "SAFEGUARD",
+    "PAIN SPLIT",
+    "SACRED FIRE",
+    "MAGNITUDE",
+    "DYNAMICPUNCH",
+    "MEGAHORN",
+    "DRAGONBREATH",
+    "BATON PASS",
+    "ENCORE",
+    "PURSUIT",
+    "RAPID SPIN",
+    "SWEET SCENT",
+    "IRON TAIL",
+    "METAL CLAW",
+    "VITAL THROW",
+    "MORNING SUN",
+    "SYNTHESIS",
+    "MOONLIGHT",
+    "HIDDEN POWER",
+    "CROSS CHOP",
+    "TWISTER",
+    "RAIN DANCE",
+    "SUNNY DAY",
+    "CRUNCH",
+    "MIRROR COAT",
+    "PSYCH UP",
+    "EXTREMESPEED",
+    "ANCIENTPOWER",
+    "SHADOW BALL",
+    "FUTURE SIGHT",
+    "ROCK SMASH",
+    "WHIRLPOOL",
+    "BEAT UP",
+    "FAKE OUT",
+    "UPROAR",
+    "STOCKPILE",
+    "SPIT UP",
+    "SWALLOW",
+    "HEAT WAVE",
+    "HAIL",
+    "TORMENT",
+    "FLATTER",
+    "WILL-O-WISP",
+    "MEMENTO",
+    "FACADE",
+    "FOCUS PUNCH",
+    "SMELLINGSALT",
+    "FOLLOW ME",
+    "NATURE POWER",
+    "CHARGE",
+    "TAUNT",
+    "HELPING HAND",
+    "TRICK",
+    "ROLE PLAY",
+    "WISH",
+    "ASSIST",
+    "INGRAIN",
+    "SUPERPOWER",
+    "MAGIC COAT",
+    "RECYCLE",
+    "REVENGE",
+    "BRICK BREAK",
+    "YAWN",
+    "KNOCK OFF",
+    "ENDEAVOR",
+    "ERUPTION",
+    "SKILL SWAP",
+    "IMPRISON",
+    "REFRESH",
+    "GRUDGE",
+    "SNATCH",
+    "SECRET POWER",
+    "DIVE",
+    "ARM THRUST",
+    "CAMOUFLAGE",
+    "TAIL GLOW",
+    "LUSTER PURGE",
+    "MIST BALL",
+    "FEATHERDANCE",
+    "TEETER DANCE",
+    "BLAZE KICK",
+    "MUD SPORT",
+    "ICE BALL",
+    "NEEDLE ARM",
+    "SLACK OFF",
+    "HYPER VOICE",
+    "POISON FANG",
+    "CRUSH CLAW",
+    "BLAST BURN",
+    "HYDRO CANNON",
+    "METEOR MASH",
+    "ASTONISH",
+    "WEATHER BALL",
+    "AROMATHERAPY",
+    "FAKE TEARS",
+    "AIR CUTTER",
+    "OVERHEAT",
+    "ODOR SLEUTH",
+    "ROCK TOMB",
+    "SILVER WIND",
+    "METAL SOUND",
+    "GRASSWHISTLE",
+    "TICKLE",
+    "COSMIC POWER",
+    "WATER SPOUT",
+    "SIGNAL BEAM",
+    "SHADOW PUNCH",
+    "EXTRASENSORY",
+    "SKY UPPERCUT",
+    "SAND TOMB",
+    "SHEER COLD",
+    "MUDDY WATER",
+    "BULLET SEED",
+    "AERIAL ACE",
+    "ICICLE SPEAR",
+    "IRON DEFENSE",
+    "BLOCK",
+    "HOWL",
+    "DRAGON CLAW",
+    "FRENZY PLANT",
+    "BULK UP",
+    "BOUNCE",
+    "MUD SHOT",
+    "POISON TAIL",
+    "COVET",
+    "VOLT TACKLE",
+    "MAGICAL LEAF",
+    "WATER SPORT",
+    "CALM MIND",
+    "LEAF BLADE",
+    "DRAGON DANCE",
+    "ROCK BLAST",
+    "SHOCK WAVE",
+    "WATER PULSE",
+    "DOOM DESIRE",
+    "PSYCHO BOOST",
+];
+
+/// A move that exists in the Gen3 games.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Move(u16);
+
+/// Error returned when a move id is not one of the moves of the games.
+#[derive(Debug)]
+pub struct InvalidMove;
+
+impl Move {
+    pub fn new(id: u16) -> Result<Self, InvalidMove> {
+        match id {
+            1..=LAST_MOVE => Ok(Move(id)),
+            _ => Err(InvalidMove),
+        }
+    }
+    pub fn id(self) -> u16 {
+        self.0
+    }
+    /// The English name of the move.
+    pub fn name(self) -> &'static str {
+        MOVE_NAMES[usize::from(self.0) - 1]
+    }
+}
+
+/// The base PP of the moves.
+#[derive(Clone, Debug, Default)]
+pub struct MoveTable {
+    pp: HashMap<Move, u8>,
+}
+
+impl MoveTable {
+    pub fn set_pp(&mut self, move_: Move, pp: u8) {
+        self.pp.insert(move_, pp);
+    }
+    pub fn pp(&self, move_: Move) -> Option<u8> {
+        self.pp.get(&move_).cloned()
+    }
+    /// Load the base PP of the moves from a text file, with one number per line, for each move
+    /// in order from id 1.
+    pub fn load_pp<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut table = MoveTable::default();
+        for (line, id) in fs::read_to_string(path)?.lines().zip(1..=LAST_MOVE) {
+            let pp = line
+                .trim()
+                .parse()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            table.set_pp(Move(id), pp);
+        }
+        Ok(table)
+    }
+}
+
+/// Whether `move_` is in `learnset`, counting level-up moves up to `level`.
+pub(crate) fn learns(learnset: &Learnset, move_: Move, level: u8) -> bool {
+    learnset
+        .level_up
+        .iter()
+        .any(|&(at, id)| id == move_.0 && at <= level)
+        || learnset.tm_hm.contains(&move_.0)
+        || learnset.tutor.contains(&move_.0)
+        || learnset.egg.contains(&move_.0)
+}
+
+/// Error returned when a move can't be given to a Pokémon.
+#[derive(Debug)]
+pub struct CantSetMove;
+
+impl Pokemon {
+    /// The moves in each slot. Empty slots are `None`.
+    pub fn moves(&self) -> [Option<Move>; N_MOVE_SLOTS] {
+        let attacks = &self.data.attacks;
+        [attacks.move1, attacks.move2, attacks.move3, attacks.move4].map(|id| Move::new(id).ok())
+    }
+    /// The PP of the move in `slot` when fully restored, with its PP Ups applied.
+    pub fn max_pp(&self, slot: usize, moves: &MoveTable) -> Option<u8> {
+        let move_ = (*self.moves().get(slot)?)?;
+        let base = u32::from(moves.pp(move_)?);
+        let pp_ups = u32::from(self.data.growth.pp_bonuses >> (slot * 2)) & 0x3;
+        Some((base + base * 20 * pp_ups / 100) as u8)
+    }
+    /// Put `move_` in `slot`, or empty the slot with `None`.
+    ///
+    /// Like when a Pokémon learns a move in the game, its PP is filled from `moves`, and the
+    /// PP Ups used on the slot are lost.
+    ///
+    /// Fails if `slot` is not a valid slot, the Pokémon already knows the move, or its PP is
+    /// not in `moves`.
+    pub fn set_move(
+        &mut self,
+        slot: usize,
+        move_: Option<Move>,
+        moves: &MoveTable,
+    ) -> Result<(), CantSetMove> {
+        if slot >= N_MOVE_SLOTS {
+            return Err(CantSetMove);
+        }
+        let (id, pp) = match move_ {
+            Some(move_) => {
+                let known = self.moves();
+                if known
+                    .iter()
+                    .enumerate()
+                    .any(|(i, &m)| i != slot && m == Some(move_))
+                {
+                    return Err(CantSetMove);
+                }
+                (move_.0, moves.pp(move_).ok_or(CantSetMove)?)
+            }
+            None => (0, 0),
+        };
+        let attacks = &mut self.data.attacks;
+        let (move_slot, pp_slot) = match slot {
+            0 => (&mut attacks.move1, &mut attacks.pp1),
+            1 => (&mut attacks.move2, &mut attacks.pp2),
+            2 => (&mut attacks.move3, &mut attacks.pp3),
+            _ => (&mut attacks.move4, &mut attacks.pp4),
+        };
+        *move_slot = id;
+        *pp_slot = pp;
+        self.data.growth.pp_bonuses &= !(0x3 << (slot * 2));
+        Ok(())
+    }
+    /// Whether the species of the Pokémon can learn `move_` in `game` according to `species`,
+    /// by leveling up to its current level, TM or HM, move tutor, or as an egg move.
+    pub fn can_learn(&self, move_: Move, species: &SpeciesTable, game: GameVersion) -> bool {
+        let info = match species.get(self.data.growth.species) {
+            Some(info) => info,
+            None => return false,
+        };
+        info.learnsets
+            .get(game)
+            .is_some_and(|learnset| learns(learnset, move_, self.level(info)))
+    }
+    /// Like `set_move`, but also fails if the Pokémon can't learn the move in `game`, see
+    /// `can_learn`.
+    pub fn teach_move(
+        &mut self,
+        slot: usize,
+        move_: Move,
+        moves: &MoveTable,
+        species: &SpeciesTable,
+        game: GameVersion,
+    ) -> Result<(), CantSetMove> {
+        if !self.can_learn(move_, species, game) {
+            return Err(CantSetMove);
+        }
+        self.set_move(slot, Some(move_), moves)
+    }
+}
+
+#[test]
+fn test_teach_move_by_game() {
+    use crate::species::{GrowthRate, Learnset, SpeciesInfo};
+    let tackle = Move::new(33).unwrap();
+    let surf = Move::new(57).unwrap();
+    assert_eq!(surf.name(), "SURF");
+    let mut moves = MoveTable::default();
+    moves.set_pp(tackle, 35);
+    moves.set_pp(surf, 15);
+    let mut info = SpeciesInfo {
+        base_stats: [50; 6],
+        growth_rate: GrowthRate::MediumFast,
+        egg_cycles: 20,
+        evolutions: Vec::new(),
+        learnsets: Default::default(),
+    };
+    let learnset = Learnset {
+        level_up: vec![(1, tackle.id())],
+        tutor: vec![surf.id()],
+        ..Default::default()
+    };
+    info.learnsets.insert(GameVersion::Emerald, learnset);
+    let mut species = SpeciesTable::default();
+    species.insert(277, info);
+    let mut pokemon = Pokemon::default();
+    pokemon.data.growth.species = 277;
+    pokemon.data.growth.pp_bonuses = 0xFF;
+    let (emerald, fire_red) = (GameVersion::Emerald, GameVersion::FireRed);
+    assert!(pokemon.can_learn(surf, &species, emerald));
+    assert!(!pokemon.can_learn(surf, &species, fire_red));
+    assert!(
+        pokemon
+            .teach_move(1, surf, &moves, &species, fire_red)
+            .is_err()
+    );
+    pokemon
+        .teach_move(1, surf, &moves, &species, emerald)
+        .unwrap();
+    assert_eq!(pokemon.data.attacks.pp2, 15);
+    assert_eq!(pokemon.data.growth.pp_bonuses, 0xF3);
+    assert!(pokemon.set_move(0, Some(surf), &moves).is_err());
+    pokemon.set_move(0, Some(tackle), &moves).unwrap();
+    assert_eq!(pokemon.moves(), [Some(tackle), Some(surf), None, None]);
+}
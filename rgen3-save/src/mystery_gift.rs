@@ -0,0 +1,316 @@
+//! Mystery Gift data: the Wonder Card, the RAM script event tickets are given with, and the
+//! Enigma Berry. Emerald and Fire Red/Leaf Green only.
+//!
+//! Each of these is checked by the game before use, so writing them recomputes their
+//! checksums.
+
+use crate::{GameType, Save, UnsupportedGame};
+use byteorder::{ByteOrder, LittleEndian as LE};
+
+/// Size of a Wonder Card, as found in Wonder Card files.
+pub const WONDER_CARD_SIZE: usize = 0x14C;
+/// Size of a RAM script, without its checksum.
+pub const RAM_SCRIPT_SIZE: usize = 999;
+/// Size of the Enigma Berry data, without its checksum.
+pub const ENIGMA_BERRY_SIZE: usize = 48;
+
+const CARD_CRC_OFFSET: usize = 0x1C0;
+const CARD_OFFSET: usize = 0x1C4;
+const CARD_METADATA_CRC_OFFSET: usize = 0x310;
+const CARD_METADATA_OFFSET: usize = 0x314;
+const CARD_METADATA_SIZE: usize = 0x24;
+const CARD_TEXT_LEN: usize = 40;
+/// Magic byte of a valid RAM script.
+const RAM_SCRIPT_MAGIC: u8 = 51;
+/// The pointers to the description lines of the Enigma Berry, which point into RAM.
+const ENIGMA_BERRY_DESCRIPTIONS: std::ops::Range<usize> = 0x0C..0x14;
+
+struct Offsets {
+    mystery_gift: usize,
+    ram_script: usize,
+    enigma_berry: usize,
+}
+
+fn offsets(game: GameType) -> Result<Offsets, UnsupportedGame> {
+    match game {
+        GameType::RubyOrSapphire => Err(UnsupportedGame),
+        GameType::Emerald => Ok(Offsets {
+            mystery_gift: 0x322C,
+            ram_script: 0x3728,
+            enigma_berry: 0x31F8,
+        }),
+        GameType::FireredOrLeafgreen => Ok(Offsets {
+            mystery_gift: 0x3120,
+            ram_script: 0x361C,
+            enigma_berry: 0x30EC,
+        }),
+    }
+}
+
+/// The CRC-16 the game uses for Mystery Gift data.
+pub fn crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0x1121;
+    for &byte in data {
+        crc ^= u16::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0x8408
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn byte_sum(data: &[u8]) -> u32 {
+    data.iter().map(|&b| u32::from(b)).sum()
+}
+
+/// Error returned when Mystery Gift data doesn't pass the checks the game does on it.
+#[derive(Debug)]
+pub struct InvalidMysteryGift;
+
+/// A Wonder Card, which holds the text shown in the Mystery Gift menu.
+#[derive(Clone)]
+pub struct WonderCard {
+    pub raw: [u8; WONDER_CARD_SIZE],
+}
+
+impl WonderCard {
+    /// Make a Wonder Card out of the raw data of one, as found in Wonder Card files.
+    ///
+    /// Fails if it's not the right size, or doesn't pass the checks the game does.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, InvalidMysteryGift> {
+        if data.len() != WONDER_CARD_SIZE {
+            return Err(InvalidMysteryGift);
+        }
+        let mut raw = [0; WONDER_CARD_SIZE];
+        raw.copy_from_slice(data);
+        let card = WonderCard { raw };
+        if card.is_valid() {
+            Ok(card)
+        } else {
+            Err(InvalidMysteryGift)
+        }
+    }
+    fn is_valid(&self) -> bool {
+        let kind = self.raw[0x08] & 0x3;
+        let background = (self.raw[0x08] >> 2) & 0xF;
+        let send_type = self.raw[0x08] >> 6;
+        self.flag_id() != 0 && kind <= 2 && background <= 7 && send_type <= 2 && self.raw[0x09] <= 7
+    }
+    /// The event flag set when the gift is received.
+    pub fn flag_id(&self) -> u16 {
+        LE::read_u16(&self.raw[0x00..])
+    }
+    /// Species index of the icon shown on the card.
+    pub fn icon_species(&self) -> u16 {
+        LE::read_u16(&self.raw[0x02..])
+    }
+    pub fn id_number(&self) -> u32 {
+        LE::read_u32(&self.raw[0x04..])
+    }
+    fn text(&self, offset: usize) -> String {
+        rgen3_string::decode_string(&self.raw[offset..offset + CARD_TEXT_LEN])
+    }
+    pub fn title(&self) -> String {
+        self.text(0x0A)
+    }
+    pub fn subtitle(&self) -> String {
+        self.text(0x32)
+    }
+    /// The 4 lines of the body of the card.
+    pub fn body(&self) -> [String; 4] {
+        [0, 1, 2, 3].map(|i| self.text(0x5A + i * CARD_TEXT_LEN))
+    }
+}
+
+/// A script the game runs when talking to a specific person, used to give event tickets.
+#[derive(Clone)]
+pub struct RamScript {
+    pub raw: [u8; RAM_SCRIPT_SIZE],
+}
+
+impl RamScript {
+    /// Make a RAM script out of its raw data, without the checksum.
+    ///
+    /// Fails if it's not the right size, or doesn't have the magic byte the game expects.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, InvalidMysteryGift> {
+        if data.len() != RAM_SCRIPT_SIZE || data[0] != RAM_SCRIPT_MAGIC {
+            return Err(InvalidMysteryGift);
+        }
+        let mut raw = [0; RAM_SCRIPT_SIZE];
+        raw.copy_from_slice(data);
+        Ok(RamScript { raw })
+    }
+    /// The map group and number of the person running the script.
+    pub fn map(&self) -> (u8, u8) {
+        (self.raw[1], self.raw[2])
+    }
+    /// The object id of the person running the script on their map.
+    pub fn object_id(&self) -> u8 {
+        self.raw[3]
+    }
+}
+
+/// The berry given through Mystery Gift, which can have custom effects.
+#[derive(Clone)]
+pub struct EnigmaBerry {
+    pub raw: [u8; ENIGMA_BERRY_SIZE],
+}
+
+impl EnigmaBerry {
+    /// Make an Enigma Berry out of its raw data, without the checksum.
+    ///
+    /// Fails if it's not the right size, or doesn't pass the checks the game does.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, InvalidMysteryGift> {
+        if data.len() != ENIGMA_BERRY_SIZE {
+            return Err(InvalidMysteryGift);
+        }
+        let mut raw = [0; ENIGMA_BERRY_SIZE];
+        raw.copy_from_slice(data);
+        let berry = EnigmaBerry { raw };
+        if berry.is_valid() {
+            Ok(berry)
+        } else {
+            Err(InvalidMysteryGift)
+        }
+    }
+    fn is_valid(&self) -> bool {
+        // Growth stage duration and max yield
+        self.raw[0x14] != 0 && self.raw[0x0A] != 0
+    }
+    pub fn name(&self) -> String {
+        rgen3_string::decode_string(&self.raw[..7])
+    }
+    /// The sum of the bytes, which the game computes with the description pointers zeroed.
+    fn checksum(&self) -> u32 {
+        let mut raw = self.raw;
+        raw[ENIGMA_BERRY_DESCRIPTIONS].fill(0);
+        byte_sum(&raw)
+    }
+}
+
+impl Save {
+    /// The Wonder Card, if there is a valid one.
+    pub fn wonder_card(&self) -> Result<Option<WonderCard>, UnsupportedGame> {
+        let offset = offsets(self.game_type())?.mystery_gift;
+        let mut raw = [0; WONDER_CARD_SIZE];
+        self.read_game_state(offset + CARD_OFFSET, &mut raw);
+        let mut crc = [0; 4];
+        self.read_game_state(offset + CARD_CRC_OFFSET, &mut crc);
+        let card = WonderCard { raw };
+        if u32::from(crc16(&card.raw)) == LE::read_u32(&crc) && card.is_valid() {
+            Ok(Some(card))
+        } else {
+            Ok(None)
+        }
+    }
+    /// Replace the Wonder Card, resetting its stats and stamps like the game does.
+    ///
+    /// This doesn't give the gift itself, which is done by the RAM script.
+    pub fn set_wonder_card(&mut self, card: &WonderCard) -> Result<(), UnsupportedGame> {
+        let offset = offsets(self.game_type())?.mystery_gift;
+        self.write_game_state(offset + CARD_OFFSET, &card.raw);
+        let mut crc = [0; 4];
+        LE::write_u32(&mut crc, u32::from(crc16(&card.raw)));
+        self.write_game_state(offset + CARD_CRC_OFFSET, &crc);
+        // The metadata is cleared without a checksum, except for the icon species that comes
+        // after the battle and trade counts
+        let mut metadata = [0; CARD_METADATA_SIZE];
+        LE::write_u16(&mut metadata[6..], card.icon_species());
+        self.write_game_state(offset + CARD_METADATA_CRC_OFFSET, &[0; 4]);
+        self.write_game_state(offset + CARD_METADATA_OFFSET, &metadata);
+        Ok(())
+    }
+    /// The RAM script, if there is a valid one.
+    pub fn ram_script(&self) -> Result<Option<RamScript>, UnsupportedGame> {
+        let offset = offsets(self.game_type())?.ram_script;
+        let mut checksum = [0; 4];
+        self.read_game_state(offset, &mut checksum);
+        let mut raw = [0; RAM_SCRIPT_SIZE];
+        self.read_game_state(offset + 4, &mut raw);
+        if raw[0] == RAM_SCRIPT_MAGIC && byte_sum(&raw) == LE::read_u32(&checksum) {
+            Ok(Some(RamScript { raw }))
+        } else {
+            Ok(None)
+        }
+    }
+    pub fn set_ram_script(&mut self, script: &RamScript) -> Result<(), UnsupportedGame> {
+        let offset = offsets(self.game_type())?.ram_script;
+        let mut checksum = [0; 4];
+        LE::write_u32(&mut checksum, byte_sum(&script.raw));
+        self.write_game_state(offset, &checksum);
+        self.write_game_state(offset + 4, &script.raw);
+        Ok(())
+    }
+    /// The Enigma Berry, if there is a valid one.
+    pub fn enigma_berry(&self) -> Result<Option<EnigmaBerry>, UnsupportedGame> {
+        let offset = offsets(self.game_type())?.enigma_berry;
+        let mut raw = [0; ENIGMA_BERRY_SIZE];
+        self.read_game_state(offset, &mut raw);
+        let mut checksum = [0; 4];
+        self.read_game_state(offset + ENIGMA_BERRY_SIZE, &mut checksum);
+        let berry = EnigmaBerry { raw };
+        if berry.checksum() == LE::read_u32(&checksum) && berry.is_valid() {
+            Ok(Some(berry))
+        } else {
+            Ok(None)
+        }
+    }
+    pub fn set_enigma_berry(&mut self, berry: &EnigmaBerry) -> Result<(), UnsupportedGame> {
+        let offset = offsets(self.game_type())?.enigma_berry;
+        self.write_game_state(offset, &berry.raw);
+        let mut checksum = [0; 4];
+        LE::write_u32(&mut checksum, berry.checksum());
+        self.write_game_state(offset + ENIGMA_BERRY_SIZE, &checksum);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_wonder_card_validation() {
+    let mut raw = [0; WONDER_CARD_SIZE];
+    assert!(WonderCard::from_bytes(&raw).is_err());
+    LE::write_u16(&mut raw, 0x1A0);
+    assert!(WonderCard::from_bytes(&raw).is_ok());
+    assert!(WonderCard::from_bytes(&raw[1..]).is_err());
+    // Send type 2, background 7, kind 2
+    raw[0x08] = (2 << 6) | (7 << 2) | 2;
+    assert!(WonderCard::from_bytes(&raw).is_ok());
+    raw[0x08] = 3 << 6;
+    assert!(WonderCard::from_bytes(&raw).is_err());
+    raw[0x08] = 3;
+    assert!(WonderCard::from_bytes(&raw).is_err());
+}
+
+#[test]
+fn test_crc16() {
+    assert_eq!(crc16(&[]), !0x1121);
+    let data = [0x12, 0x34, 0x56];
+    let mut changed = data;
+    changed[1] ^= 1;
+    assert_ne!(crc16(&data), crc16(&changed));
+}
+
+#[test]
+fn test_enigma_berry_checksum() {
+    use crate::GameVersion;
+    let mut save = crate::rw::blank_save(GameVersion::Emerald);
+    assert!(save.enigma_berry().unwrap().is_none());
+    let mut raw = [0; ENIGMA_BERRY_SIZE];
+    raw[0x0A] = 5;
+    raw[0x14] = 24;
+    // Description pointers, left out of the checksum
+    raw[0x0C..0x14].copy_from_slice(&[0x00, 0x10, 0x00, 0x02, 0x20, 0x10, 0x00, 0x02]);
+    let berry = EnigmaBerry::from_bytes(&raw).unwrap();
+    save.set_enigma_berry(&berry).unwrap();
+    let offset = offsets(GameType::Emerald).unwrap().enigma_berry;
+    let mut checksum = [0; 4];
+    save.read_game_state(offset + ENIGMA_BERRY_SIZE, &mut checksum);
+    assert_eq!(LE::read_u32(&checksum), 5 + 24);
+    assert_eq!(save.enigma_berry().unwrap().unwrap().raw, raw);
+    save.write_game_state(offset + 0x0A, &[6]);
+    assert!(save.enigma_berry().unwrap().is_none());
+}
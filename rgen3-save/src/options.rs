@@ -0,0 +1,149 @@
+//! The options menu settings stored in the trainer info section.
+
+use crate::GameType;
+use byteorder::{ByteOrder, LittleEndian as LE};
+
+/// Speed at which text is printed in dialogue boxes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextSpeed {
+    Slow = 0,
+    Mid = 1,
+    Fast = 2,
+}
+
+/// Whether battle animations are shown.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BattleScene {
+    On,
+    Off,
+}
+
+/// Whether the player is offered to switch after knocking out a Pokémon.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BattleStyle {
+    Shift = 0,
+    Set = 1,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sound {
+    Mono = 0,
+    Stereo = 1,
+}
+
+/// How the L and R buttons behave.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ButtonMode {
+    /// Called "Help" in Fire Red/Leaf Green, where L and R open the help system.
+    Normal = 0,
+    LR = 1,
+    LEqualsA = 2,
+}
+
+/// Decoded options menu settings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Options {
+    pub text_speed: TextSpeed,
+    pub battle_scene: BattleScene,
+    pub battle_style: BattleStyle,
+    pub sound: Sound,
+    pub button_mode: ButtonMode,
+    /// Index of the window frame style. Ruby/Sapphire/Emerald have 20 styles,
+    /// Fire Red/Leaf Green have 10.
+    pub frame_type: u8,
+}
+
+/// Error returned when the options data contains values the game doesn't use.
+#[derive(Debug)]
+pub struct InvalidOptions;
+
+const TEXT_SPEED_MASK: u16 = 0b0000_0000_0111;
+const FRAME_TYPE_SHIFT: u16 = 3;
+const FRAME_TYPE_MASK: u16 = 0b0000_1111_1000;
+const SOUND_BIT: u16 = 1 << 8;
+const BATTLE_STYLE_BIT: u16 = 1 << 9;
+const BATTLE_SCENE_OFF_BIT: u16 = 1 << 10;
+
+pub(crate) fn n_frame_types(game: GameType) -> u8 {
+    match game {
+        GameType::RubyOrSapphire | GameType::Emerald => 20,
+        GameType::FireredOrLeafgreen => 10,
+    }
+}
+
+impl Options {
+    pub(crate) fn decode(data: &[u8; 3]) -> Result<Self, InvalidOptions> {
+        let button_mode = match data[0] {
+            0 => ButtonMode::Normal,
+            1 => ButtonMode::LR,
+            2 => ButtonMode::LEqualsA,
+            _ => return Err(InvalidOptions),
+        };
+        let bits = LE::read_u16(&data[1..]);
+        let text_speed = match bits & TEXT_SPEED_MASK {
+            0 => TextSpeed::Slow,
+            1 => TextSpeed::Mid,
+            2 => TextSpeed::Fast,
+            _ => return Err(InvalidOptions),
+        };
+        Ok(Options {
+            text_speed,
+            battle_scene: if bits & BATTLE_SCENE_OFF_BIT != 0 {
+                BattleScene::Off
+            } else {
+                BattleScene::On
+            },
+            battle_style: if bits & BATTLE_STYLE_BIT != 0 {
+                BattleStyle::Set
+            } else {
+                BattleStyle::Shift
+            },
+            sound: if bits & SOUND_BIT != 0 {
+                Sound::Stereo
+            } else {
+                Sound::Mono
+            },
+            button_mode,
+            frame_type: ((bits & FRAME_TYPE_MASK) >> FRAME_TYPE_SHIFT) as u8,
+        })
+    }
+    /// Encode into `data`, leaving the bits not covered by `Options` (like the Emerald
+    /// region map zoom) untouched.
+    pub(crate) fn encode(&self, data: &mut [u8; 3]) {
+        data[0] = self.button_mode as u8;
+        let mut bits = LE::read_u16(&data[1..]);
+        bits &= !(TEXT_SPEED_MASK
+            | FRAME_TYPE_MASK
+            | SOUND_BIT
+            | BATTLE_STYLE_BIT
+            | BATTLE_SCENE_OFF_BIT);
+        bits |= self.text_speed as u16;
+        bits |= (u16::from(self.frame_type) << FRAME_TYPE_SHIFT) & FRAME_TYPE_MASK;
+        if self.sound == Sound::Stereo {
+            bits |= SOUND_BIT;
+        }
+        if self.battle_style == BattleStyle::Set {
+            bits |= BATTLE_STYLE_BIT;
+        }
+        if self.battle_scene == BattleScene::Off {
+            bits |= BATTLE_SCENE_OFF_BIT;
+        }
+        LE::write_u16(&mut data[1..], bits);
+    }
+}
+
+#[test]
+fn test_options_roundtrip() {
+    // Fast text, frame 5, stereo, set style, battle scene off, region map zoom bit set
+    let orig = [2, 0b0010_1010, 0b0000_1111];
+    let options = Options::decode(&orig).unwrap();
+    assert_eq!(options.text_speed, TextSpeed::Fast);
+    assert_eq!(options.frame_type, 5);
+    assert_eq!(options.sound, Sound::Stereo);
+    assert_eq!(options.battle_style, BattleStyle::Set);
+    assert_eq!(options.battle_scene, BattleScene::Off);
+    assert_eq!(options.button_mode, ButtonMode::LEqualsA);
+    let mut data = [0, 0, 0b0000_1000];
+    options.encode(&mut data);
+    assert_eq!(data, orig);
+}
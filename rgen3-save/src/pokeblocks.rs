@@ -0,0 +1,142 @@
+//! The PokéBlock case. Ruby/Sapphire/Emerald only.
+//!
+//! Feeding PokéBlocks raises the contest stats in `PokemonEvsAndCondition`.
+
+use crate::{GameType, Save, UnsupportedGame};
+
+pub const N_POKEBLOCKS: usize = 40;
+const POKEBLOCK_SIZE: usize = 8;
+
+fn pokeblocks_offset(game: GameType) -> Result<usize, UnsupportedGame> {
+    match game {
+        GameType::RubyOrSapphire => Ok(0x7F8),
+        GameType::Emerald => Ok(0x848),
+        GameType::FireredOrLeafgreen => Err(UnsupportedGame),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PokeblockColor {
+    Red = 1,
+    Blue,
+    Pink,
+    Green,
+    Yellow,
+    Purple,
+    Indigo,
+    Brown,
+    LiteBlue,
+    Olive,
+    Gray,
+    Black,
+    White,
+    Gold,
+}
+
+impl PokeblockColor {
+    fn from_u8(value: u8) -> Option<Self> {
+        use PokeblockColor::*;
+        let colors = [
+            Red, Blue, Pink, Green, Yellow, Purple, Indigo, Brown, LiteBlue, Olive, Gray, Black,
+            White, Gold,
+        ];
+        colors.get(usize::from(value).checked_sub(1)?).cloned()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pokeblock {
+    pub color: PokeblockColor,
+    pub spicy: u8,
+    pub dry: u8,
+    pub sweet: u8,
+    pub bitter: u8,
+    pub sour: u8,
+    /// How much the PokéBlock fills the Pokémon up. The lower the better.
+    pub feel: u8,
+}
+
+impl Pokeblock {
+    fn read(data: &[u8]) -> Option<Self> {
+        Some(Pokeblock {
+            color: PokeblockColor::from_u8(data[0])?,
+            spicy: data[1],
+            dry: data[2],
+            sweet: data[3],
+            bitter: data[4],
+            sour: data[5],
+            feel: data[6],
+        })
+    }
+    fn write(&self, data: &mut [u8]) {
+        data[0] = self.color as u8;
+        data[1] = self.spicy;
+        data[2] = self.dry;
+        data[3] = self.sweet;
+        data[4] = self.bitter;
+        data[5] = self.sour;
+        data[6] = self.feel;
+    }
+}
+
+/// A copy of the PokéBlock case of a save.
+///
+/// Get it with `Save::pokeblock_case`, and write it back with `Save::set_pokeblock_case`.
+#[derive(Clone, Debug)]
+pub struct PokeblockCase {
+    /// Empty slots, or ones with an invalid color, are `None`. Slots with an invalid color are
+    /// written back as they were read, unless a PokéBlock is put in them.
+    pub slots: [Option<Pokeblock>; N_POKEBLOCKS],
+    raw: [u8; N_POKEBLOCKS * POKEBLOCK_SIZE],
+}
+
+impl Save {
+    pub fn pokeblock_case(&self) -> Result<PokeblockCase, UnsupportedGame> {
+        let offset = pokeblocks_offset(self.game_type())?;
+        let mut raw = [0; N_POKEBLOCKS * POKEBLOCK_SIZE];
+        self.read_game_state(offset, &mut raw);
+        let mut case = PokeblockCase {
+            slots: [None; N_POKEBLOCKS],
+            raw,
+        };
+        for (slot, data) in case.slots.iter_mut().zip(raw.chunks(POKEBLOCK_SIZE)) {
+            *slot = Pokeblock::read(data);
+        }
+        Ok(case)
+    }
+    pub fn set_pokeblock_case(&mut self, case: &PokeblockCase) -> Result<(), UnsupportedGame> {
+        let offset = pokeblocks_offset(self.game_type())?;
+        let mut raw = case.raw;
+        for (slot, data) in case.slots.iter().zip(raw.chunks_mut(POKEBLOCK_SIZE)) {
+            match slot {
+                Some(pokeblock) => pokeblock.write(data),
+                // Only clear the PokéBlocks that were taken away, and keep the invalid ones
+                None if Pokeblock::read(data).is_some() => data.fill(0),
+                None => {}
+            }
+        }
+        self.write_game_state(offset, &raw);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_pokeblock_roundtrip() {
+    let pokeblock = Pokeblock {
+        color: PokeblockColor::Gold,
+        spicy: 1,
+        dry: 2,
+        sweet: 3,
+        bitter: 4,
+        sour: 5,
+        feel: 6,
+    };
+    let mut data = [0; POKEBLOCK_SIZE];
+    pokeblock.write(&mut data);
+    assert_eq!(data, [14, 1, 2, 3, 4, 5, 6, 0]);
+    assert_eq!(Pokeblock::read(&data), Some(pokeblock));
+    data[0] = 15;
+    assert_eq!(Pokeblock::read(&data), None);
+    data[0] = 0;
+    assert_eq!(Pokeblock::read(&data), None);
+}
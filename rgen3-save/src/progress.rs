@@ -0,0 +1,77 @@
+//! Gym badges and story milestones, on top of the event flags.
+
+use crate::{GameType, KnownFlag, Save, UnsupportedGame};
+
+/// Offset of the Pokédex "national magic" byte in the trainer info section.
+const NATIONAL_DEX_MAGIC_OFFSET: usize = 0x1A;
+
+/// The values the game checks to decide whether the National Dex is enabled: the magic byte
+/// in the trainer info, and a script variable along with its expected value.
+fn national_dex_markers(game: GameType) -> (u8, u16, u16) {
+    match game {
+        GameType::RubyOrSapphire | GameType::Emerald => (0xDA, 0x4046, 0x0302),
+        GameType::FireredOrLeafgreen => (0xB9, 0x404E, 0x6258),
+    }
+}
+
+impl Save {
+    /// Which of the 8 badges the player has.
+    pub fn badges(&self) -> [bool; 8] {
+        let flags = self.flags();
+        KnownFlag::BADGES.map(|badge| flags.get_known(badge).unwrap())
+    }
+    /// Give or take away badge `n`, from 1 to 8.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is not a valid badge number.
+    pub fn set_badge(&mut self, n: usize, value: bool) {
+        assert!((1..=8).contains(&n), "Invalid badge number: {}", n);
+        let mut flags = self.flags();
+        flags.set_known(KnownFlag::BADGES[n - 1], value).unwrap();
+        self.set_flags(&flags);
+    }
+    /// Whether the Elite Four has been beaten and the player entered the Hall of Fame.
+    pub fn elite_four_beaten(&self) -> bool {
+        self.flags().get_known(KnownFlag::GameClear).unwrap()
+    }
+    /// Whether the Pokédex has been upgraded to the National Dex.
+    pub fn national_dex_obtained(&self) -> bool {
+        let (magic, var, var_value) = national_dex_markers(self.game_type());
+        let trainer = self.sections().trainer;
+        trainer.unknown_byte(NATIONAL_DEX_MAGIC_OFFSET) == magic
+            && self.vars().get(var) == var_value
+            && self.flags().get_known(KnownFlag::NationalDex).unwrap()
+    }
+    /// Whether the player has been given the Frontier Pass, which happens on the first visit
+    /// to the Battle Frontier. Emerald only.
+    pub fn battle_frontier_opened(&self) -> Result<bool, UnsupportedGame> {
+        self.flags().get_known(KnownFlag::FrontierPass)
+    }
+    /// Whether the player has been to the Sevii Islands. Fire Red/Leaf Green only.
+    pub fn sevii_islands_unlocked(&self) -> Result<bool, UnsupportedGame> {
+        self.flags().get_known(KnownFlag::SeviiMap123)
+    }
+}
+
+#[test]
+fn test_badges() {
+    use crate::GameVersion;
+    for version in [
+        GameVersion::Ruby,
+        GameVersion::Emerald,
+        GameVersion::FireRed,
+    ] {
+        let mut save = crate::rw::blank_save(version);
+        assert_eq!(save.badges(), [false; 8]);
+        save.set_badge(8, true);
+        let mut expected = [false; 8];
+        expected[7] = true;
+        assert_eq!(save.badges(), expected);
+        assert!(!save.elite_four_beaten());
+        assert!(!save.national_dex_obtained());
+    }
+    let save = crate::rw::blank_save(GameVersion::Ruby);
+    assert!(save.battle_frontier_opened().is_err());
+    assert!(save.sevii_islands_unlocked().is_err());
+}
@@ -0,0 +1,160 @@
+//! The Recorded Battle, which Emerald keeps in the last sector of the save footer.
+
+use crate::footer::{FooterSectorKind, RECORDED_BATTLE_SIZE, SectorStatus};
+use crate::{GameType, Pokemon, Save, TRAINER_NAME_LEN, TrainerName, UnsupportedGame};
+use byteorder::{ByteOrder, LittleEndian as LE};
+
+const PARTY_SIZE: usize = 6;
+const PARTY_POKEMON_LEN: usize = 100;
+const N_BATTLERS: usize = 4;
+
+const OPPONENT_PARTY_OFFSET: usize = 0x258;
+const NAMES_OFFSET: usize = 0x4B0;
+const TRAINER_IDS_OFFSET: usize = 0x4D4;
+const BATTLE_FLAGS_OFFSET: usize = 0x4EC;
+const OPPONENT_A_OFFSET: usize = 0x4F4;
+const OPPONENT_B_OFFSET: usize = 0x4F6;
+const LEVEL_MODE_OFFSET: usize = 0x4FC;
+const FACILITY_OFFSET: usize = 0x4FD;
+
+/// A facility of the Battle Frontier.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrontierFacility {
+    Tower,
+    Dome,
+    Palace,
+    Arena,
+    Factory,
+    Pike,
+    Pyramid,
+}
+
+impl FrontierFacility {
+    fn from_u8(value: u8) -> Option<Self> {
+        use FrontierFacility::*;
+        [Tower, Dome, Palace, Arena, Factory, Pike, Pyramid]
+            .get(usize::from(value))
+            .cloned()
+    }
+}
+
+/// The header of a Recorded Battle: who fought, and with which Pokémon.
+///
+/// The recorded inputs the battle is replayed from are not decoded.
+#[derive(Debug)]
+pub struct RecordedBattle {
+    pub player_party: Vec<Pokemon>,
+    pub opponent_party: Vec<Pokemon>,
+    /// Names of the trainers on the player's side and the link opponents, the player first.
+    /// Unused slots are empty.
+    pub trainer_names: [TrainerName; N_BATTLERS],
+    pub trainer_ids: [u32; N_BATTLERS],
+    /// The kind of battle, like double or link battle, as a set of bits.
+    pub battle_flags: u32,
+    /// The trainer fought, numbered as in the facility's trainer list.
+    pub opponent: u16,
+    /// The second trainer fought in multi battles.
+    pub opponent_b: u16,
+    /// Where the battle took place. Only meaningful for Battle Frontier battles.
+    pub facility: Option<FrontierFacility>,
+    /// Whether the battle was in the open level mode rather than level 50.
+    pub open_level: bool,
+}
+
+fn read_party(data: &[u8]) -> Option<Vec<Pokemon>> {
+    data.chunks(PARTY_POKEMON_LEN)
+        .take(PARTY_SIZE)
+        .filter(|data| !data.iter().all(|&v| v == 0))
+        .map(|mut data| Pokemon::read(&mut data).ok())
+        .collect()
+}
+
+impl Save {
+    /// The Recorded Battle. Emerald only.
+    ///
+    /// Returns `None` if no battle was recorded, or the recorded one can't be decoded. Use
+    /// `Save::footer_sectors` to tell them apart.
+    pub fn recorded_battle(&self) -> Result<Option<RecordedBattle>, UnsupportedGame> {
+        if self.game_type() != GameType::Emerald {
+            return Err(UnsupportedGame);
+        }
+        let (kind, status) = self.footer_sectors()[3];
+        if kind != FooterSectorKind::RecordedBattle || status != SectorStatus::Valid {
+            return Ok(None);
+        }
+        let data = match self.footer[3].special_data() {
+            Some(data) => &data[..RECORDED_BATTLE_SIZE],
+            None => return Ok(None),
+        };
+        let (player_party, opponent_party) = match (
+            read_party(&data[..OPPONENT_PARTY_OFFSET]),
+            read_party(&data[OPPONENT_PARTY_OFFSET..NAMES_OFFSET]),
+        ) {
+            (Some(player), Some(opponent)) => (player, opponent),
+            _ => return Ok(None),
+        };
+        let mut trainer_names = [TrainerName::default(); N_BATTLERS];
+        for (i, name) in trainer_names.iter_mut().enumerate() {
+            // The names have room for a terminator
+            let offset = NAMES_OFFSET + i * (TRAINER_NAME_LEN + 1);
+            name.0
+                .copy_from_slice(&data[offset..offset + TRAINER_NAME_LEN]);
+        }
+        let mut trainer_ids = [0; N_BATTLERS];
+        LE::read_u32_into(
+            &data[TRAINER_IDS_OFFSET..TRAINER_IDS_OFFSET + 4 * N_BATTLERS],
+            &mut trainer_ids,
+        );
+        Ok(Some(RecordedBattle {
+            player_party,
+            opponent_party,
+            trainer_names,
+            trainer_ids,
+            battle_flags: LE::read_u32(&data[BATTLE_FLAGS_OFFSET..]),
+            opponent: LE::read_u16(&data[OPPONENT_A_OFFSET..]),
+            opponent_b: LE::read_u16(&data[OPPONENT_B_OFFSET..]),
+            facility: FrontierFacility::from_u8(data[FACILITY_OFFSET]),
+            open_level: data[LEVEL_MODE_OFFSET] != 0,
+        }))
+    }
+}
+
+#[test]
+fn test_recorded_battle() {
+    use crate::GameVersion;
+    let mut save = crate::rw::blank_save(GameVersion::Ruby);
+    assert!(save.recorded_battle().is_err());
+    save = crate::rw::blank_save(GameVersion::Emerald);
+    assert!(save.recorded_battle().unwrap().is_none());
+    let mut data = vec![0; RECORDED_BATTLE_SIZE];
+    let pokemon = Pokemon {
+        personality: 0x1234,
+        ..Default::default()
+    };
+    // The active data is left zeroed
+    pokemon.write_non_active(&mut &mut data[..]).unwrap();
+    data[NAMES_OFFSET] = 0xBB;
+    LE::write_u32(&mut data[TRAINER_IDS_OFFSET + 4..], 0x5678);
+    LE::write_u16(&mut data[OPPONENT_A_OFFSET..], 42);
+    data[FACILITY_OFFSET] = 4;
+    data[LEVEL_MODE_OFFSET] = 1;
+    let len = RECORDED_BATTLE_SIZE - 4;
+    let sum: u32 = data[..len].iter().map(|&b| u32::from(b)).sum();
+    LE::write_u32(&mut data[len..], sum);
+    let sector = &mut save.footer[3].data;
+    // The sentinel of a sector that has been written
+    LE::write_u32(&mut sector[..], 0xB39D);
+    sector[4..4 + RECORDED_BATTLE_SIZE].copy_from_slice(&data);
+    let battle = save.recorded_battle().unwrap().unwrap();
+    assert_eq!(battle.player_party.len(), 1);
+    assert_eq!(battle.player_party[0].personality, 0x1234);
+    assert!(battle.opponent_party.is_empty());
+    assert_eq!(battle.trainer_names[0].0[0], 0xBB);
+    assert_eq!(battle.trainer_ids[1], 0x5678);
+    assert_eq!(battle.opponent, 42);
+    assert_eq!(battle.facility, Some(FrontierFacility::Factory));
+    assert!(battle.open_level);
+    // A bad sum makes it unreadable
+    save.footer[3].data[4 + NAMES_OFFSET] = 0;
+    assert!(save.recorded_battle().unwrap().is_none());
+}
@@ -0,0 +1,48 @@
+//! The rival's name.
+//!
+//! Only Fire Red/Leaf Green let the player name their rival. Ruby/Sapphire/Emerald pick the
+//! rival (and their name) from the player's gender, so there's nothing stored to edit.
+
+use crate::{GameType, Save, TRAINER_NAME_LEN, TrainerName, UnsupportedGame};
+
+/// Offset of the rival name in the game state, in Fire Red/Leaf Green.
+const FRLG_RIVAL_NAME_OFFSET: usize = 0x3A4C;
+
+impl Save {
+    /// The name of the rival. Fire Red/Leaf Green only.
+    pub fn rival_name(&self) -> Result<TrainerName, UnsupportedGame> {
+        if self.game_type() != GameType::FireredOrLeafgreen {
+            return Err(UnsupportedGame);
+        }
+        let mut name = TrainerName::default();
+        self.read_game_state(FRLG_RIVAL_NAME_OFFSET, &mut name.0);
+        Ok(name)
+    }
+    /// Rename the rival. Fire Red/Leaf Green only.
+    pub fn set_rival_name(&mut self, name: &TrainerName) -> Result<(), UnsupportedGame> {
+        if self.game_type() != GameType::FireredOrLeafgreen {
+            return Err(UnsupportedGame);
+        }
+        // The game keeps room for a terminator after the longest possible name
+        let mut raw = [0xFF; TRAINER_NAME_LEN + 1];
+        raw[..TRAINER_NAME_LEN].copy_from_slice(&name.0);
+        self.write_game_state(FRLG_RIVAL_NAME_OFFSET, &raw);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_rival_name() {
+    use crate::GameVersion;
+    let mut save = crate::rw::blank_save(GameVersion::LeafGreen);
+    let mut name = TrainerName::default();
+    rgen3_string::encode_string("GARY", &mut name.0);
+    save.set_rival_name(&name).unwrap();
+    assert_eq!(save.rival_name().unwrap().0, name.0);
+    let mut terminator = [0];
+    save.read_game_state(FRLG_RIVAL_NAME_OFFSET + TRAINER_NAME_LEN, &mut terminator);
+    assert_eq!(terminator, [0xFF]);
+    let mut save = crate::rw::blank_save(GameVersion::Sapphire);
+    assert!(save.rival_name().is_err());
+    assert!(save.set_rival_name(&name).is_err());
+}
@@ -0,0 +1,173 @@
+//! The roaming legendary Pokémon: Latios or Latias in Ruby/Sapphire/Emerald, and Raikou,
+//! Entei or Suicune in Fire Red/Leaf Green.
+//!
+//! Only the roamer's own state is saved. Its location is kept in RAM and picked again when
+//! the game is started, so it can't be changed through the save.
+
+use crate::{GameType, Save};
+use byteorder::{ByteOrder, LittleEndian as LE};
+
+const ROAMER_SIZE: usize = 0x14;
+
+fn roamer_offset(game: GameType) -> usize {
+    match game {
+        GameType::RubyOrSapphire => 0x3144,
+        GameType::Emerald => 0x31DC,
+        GameType::FireredOrLeafgreen => 0x30D0,
+    }
+}
+
+/// Base HP of the species that can roam, by species index.
+fn roamer_base_hp(species: u16) -> Option<u16> {
+    match species {
+        // Raikou, Entei, Suicune
+        243 => Some(90),
+        244 => Some(115),
+        245 => Some(100),
+        // Latias, Latios
+        407 | 408 => Some(80),
+        _ => None,
+    }
+}
+
+/// A non-volatile status condition, as stored in the low byte of the status of a Pokémon.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatusCondition {
+    Healthy,
+    /// Asleep for this many more turns.
+    Sleep(u8),
+    Poison,
+    Burn,
+    Freeze,
+    Paralysis,
+    /// Badly poisoned.
+    Toxic,
+}
+
+impl StatusCondition {
+    pub fn from_bits(bits: u8) -> Self {
+        match bits {
+            0 => StatusCondition::Healthy,
+            _ if bits & 0x7 != 0 => StatusCondition::Sleep(bits & 0x7),
+            _ if bits & 0x08 != 0 => StatusCondition::Poison,
+            _ if bits & 0x10 != 0 => StatusCondition::Burn,
+            _ if bits & 0x20 != 0 => StatusCondition::Freeze,
+            _ if bits & 0x40 != 0 => StatusCondition::Paralysis,
+            _ => StatusCondition::Toxic,
+        }
+    }
+    pub fn to_bits(self) -> u8 {
+        match self {
+            StatusCondition::Healthy => 0,
+            StatusCondition::Sleep(turns) => turns & 0x7,
+            StatusCondition::Poison => 0x08,
+            StatusCondition::Burn => 0x10,
+            StatusCondition::Freeze => 0x20,
+            StatusCondition::Paralysis => 0x40,
+            StatusCondition::Toxic => 0x80,
+        }
+    }
+}
+
+/// The state of the roaming legendary.
+///
+/// Get it with `Save::roamer`, and write it back with `Save::set_roamer`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Roamer {
+    /// The IVs, packed the same way as for other Pokémon.
+    pub ivs: u32,
+    pub personality: u32,
+    /// Species index.
+    pub species: u16,
+    pub current_hp: u16,
+    pub level: u8,
+    pub status: StatusCondition,
+    /// Coolness, beauty, cuteness, smartness and toughness.
+    pub contest_stats: [u8; 5],
+    /// Whether the roamer is still out there. It stops once caught or defeated.
+    pub active: bool,
+}
+
+impl Roamer {
+    fn read(data: &[u8]) -> Self {
+        let mut contest_stats = [0; 5];
+        contest_stats.copy_from_slice(&data[0x0E..0x13]);
+        Roamer {
+            ivs: LE::read_u32(&data[0x00..]),
+            personality: LE::read_u32(&data[0x04..]),
+            species: LE::read_u16(&data[0x08..]),
+            current_hp: LE::read_u16(&data[0x0A..]),
+            level: data[0x0C],
+            status: StatusCondition::from_bits(data[0x0D]),
+            contest_stats,
+            active: data[0x13] != 0,
+        }
+    }
+    fn write(&self, data: &mut [u8]) {
+        LE::write_u32(&mut data[0x00..], self.ivs);
+        LE::write_u32(&mut data[0x04..], self.personality);
+        LE::write_u16(&mut data[0x08..], self.species);
+        LE::write_u16(&mut data[0x0A..], self.current_hp);
+        data[0x0C] = self.level;
+        data[0x0D] = self.status.to_bits();
+        data[0x0E..0x13].copy_from_slice(&self.contest_stats);
+        data[0x13] = self.active as u8;
+    }
+    /// The maximum HP of the roamer at its level, or `None` if its species can't roam.
+    pub fn max_hp(&self) -> Option<u16> {
+        let base = roamer_base_hp(self.species)?;
+        let iv = (self.ivs & 0x1F) as u16;
+        let level = u16::from(self.level);
+        Some((2 * base + iv) * level / 100 + level + 10)
+    }
+    /// Put the roamer back in the wild, fully healed.
+    ///
+    /// Returns `false` and does nothing if its species can't roam.
+    pub fn reset(&mut self) -> bool {
+        let max_hp = match self.max_hp() {
+            Some(max_hp) => max_hp,
+            None => return false,
+        };
+        self.current_hp = max_hp;
+        self.status = StatusCondition::Healthy;
+        self.active = true;
+        true
+    }
+}
+
+impl Save {
+    pub fn roamer(&self) -> Roamer {
+        let mut raw = [0; ROAMER_SIZE];
+        self.read_game_state(roamer_offset(self.game_type()), &mut raw);
+        Roamer::read(&raw)
+    }
+    pub fn set_roamer(&mut self, roamer: &Roamer) {
+        let offset = roamer_offset(self.game_type());
+        let mut raw = [0; ROAMER_SIZE];
+        self.read_game_state(offset, &mut raw);
+        roamer.write(&mut raw);
+        self.write_game_state(offset, &raw);
+    }
+}
+
+#[test]
+fn test_roamer() {
+    use crate::GameVersion;
+    let mut save = crate::rw::blank_save(GameVersion::Ruby);
+    let mut roamer = save.roamer();
+    assert!(!roamer.reset());
+    roamer.species = 408;
+    roamer.level = 40;
+    roamer.ivs = 31;
+    roamer.status = StatusCondition::Sleep(3);
+    // (2 * 80 + 31) * 40 / 100 + 40 + 10
+    assert_eq!(roamer.max_hp(), Some(126));
+    assert!(roamer.reset());
+    assert_eq!(roamer.current_hp, 126);
+    assert_eq!(roamer.status, StatusCondition::Healthy);
+    save.set_roamer(&roamer);
+    assert_eq!(save.roamer(), roamer);
+    for status in [StatusCondition::Sleep(2), StatusCondition::Toxic] {
+        assert_eq!(StatusCondition::from_bits(status.to_bits()), status);
+    }
+}
@@ -1,14 +1,16 @@
+use crate::footer::{FOOTER_SECTOR_SIZE, FooterSector, N_FOOTER_SECTORS};
 use crate::util::LowerUpper;
+use crate::version;
 use crate::{
-    DATA_SIZE, EM_RU_SA_TEAMANDITEMS_REM_LEN, EM_RU_SA_TEAMANDITEMS_UNK_LEN,
-    FR_LG_TEAMANDITEMS_REM_LEN, FR_LG_TEAMANDITEMS_UNK_LEN, FRLG_PLAYERINFO_TRAILING_DATA_SIZE,
-    FRLG_PLAYERINFO_UNKNOWN_CHUNK_SIZE, Game, GameType, Gender, N_BOXES, POKEMON_NICK_LEN,
-    PcBuffer, PokeBox, Pokemon, PokemonActiveData, PokemonAttacks, PokemonData,
-    PokemonEvsAndCondition, PokemonGrowth, PokemonMisc, PokemonNick, PokemonStorage,
-    RS_EM_PLAYERINFO_TRAILING_DATA_SIZE, Save, SaveBlock, Section, SectionData,
-    TEAMANDITEMS_POKE_LEN, TRAINER_INFO_UNKNOWN_3_SIZE, TRAINER_NAME_LEN, TeamAndItems,
-    TeamAndItemsRemaining, TeamAndItemsUnknown, Time, TrainerInfo, TrainerName,
-    UNKNOWN_SAVE_FOOTER_SIZE,
+    Confidence, DATA_SIZE, DetectedVersion, EM_RU_SA_TEAMANDITEMS_REM_LEN,
+    EM_RU_SA_TEAMANDITEMS_UNK_LEN, FR_LG_TEAMANDITEMS_REM_LEN, FR_LG_TEAMANDITEMS_UNK_LEN,
+    FRLG_PLAYERINFO_TRAILING_DATA_SIZE, FRLG_PLAYERINFO_UNKNOWN_CHUNK_SIZE, Game, GameStateBuffer,
+    GameType, GameVersion, Gender, N_BOXES, N_GAME_STATE_SECTIONS, POKEMON_NICK_LEN, PcBuffer,
+    PokeBox, Pokemon, PokemonActiveData, PokemonAttacks, PokemonData, PokemonEvsAndCondition,
+    PokemonGrowth, PokemonMisc, PokemonNick, PokemonStorage, RS_EM_PLAYERINFO_TRAILING_DATA_SIZE,
+    Save, SaveBlock, SaveSections, Section, SectionData, TEAMANDITEMS_POKE_LEN,
+    TRAINER_INFO_UNKNOWN_3_SIZE, TRAINER_NAME_LEN, TeamAndItems, TeamAndItemsRemaining,
+    TeamAndItemsUnknown, Time, TrainerInfo, TrainerName,
 };
 use byteorder::{LittleEndian as LE, ReadBytesExt, WriteBytesExt};
 use log::debug;
@@ -16,6 +18,15 @@ use std::error::Error;
 use std::io::prelude::*;
 use std::io::{self, SeekFrom};
 
+/// Sum of the 32 bit words of `data`, folded into 16 bits.
+pub(crate) fn checksum(data: &[u8]) -> u16 {
+    let sum = data.chunks(4).fold(0u32, |sum, word| {
+        sum.wrapping_add(u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+    });
+    let (lower, upper) = sum.split();
+    upper.wrapping_add(lower)
+}
+
 trait SectionWrite {
     fn id(&self) -> u16;
     fn cksum_area_len(&self) -> u64;
@@ -26,24 +37,15 @@ trait SectionWrite {
         // Write section id
         buf.write_u16::<LE>(self.id())?;
         // Calculate and write checksum
-        let mut cksum: u32 = 0;
-        let mut reader = &buf[..];
-        let mut amount_read = 0;
-
-        while amount_read < self.cksum_area_len() {
-            cksum = cksum.wrapping_add(reader.read_u32::<LE>()?);
-            amount_read += 4;
-        }
+        let cksum = checksum(&buf[..self.cksum_area_len() as usize]);
         writer.write_all(&buf)?;
-        let (lower, upper) = cksum.split();
-        let cksum = upper.wrapping_add(lower);
         debug!("Calculated checksum is {}", cksum);
         writer.write_u16::<LE>(cksum)
     }
 }
 
 impl TrainerInfo {
-    fn read<R: Read>(reader: &mut R) -> Result<Self, Box<dyn Error>> {
+    fn read<R: Read>(reader: &mut R, game_type: GameType) -> Result<Self, Box<dyn Error>> {
         let mut name_buffer = [0u8; 7];
         reader.read_exact(&mut name_buffer)?;
         debug!(
@@ -73,7 +75,7 @@ impl TrainerInfo {
         reader.read_exact(&mut options_data)?;
         let mut unknown_3 = [0u8; TRAINER_INFO_UNKNOWN_3_SIZE];
         reader.read_exact(&mut unknown_3)?;
-        let game = Game::read(reader)?;
+        let game = Game::read(reader, game_type)?;
         debug!("Game info: {:?}", game);
         Ok(TrainerInfo {
             name: TrainerName(name_buffer),
@@ -136,6 +138,7 @@ struct ReadSession {
     team_and_items_index: Option<usize>,
     nonexistent: bool,
     box_indexes: [usize; N_BOXES],
+    game_state_indexes: [Option<usize>; N_GAME_STATE_SECTIONS],
 }
 
 impl Section {
@@ -176,8 +179,10 @@ impl Section {
         reader.seek(SeekFrom::Start(data_pos))?;
         let data = match id {
             0 => {
-                let info = TrainerInfo::read(reader)?;
-                session.game_type = Some(GameType::from(&info.game));
+                let game_type = session
+                    .game_type
+                    .ok_or("Could not determine the game layout of the block")?;
+                let info = TrainerInfo::read(reader, game_type)?;
                 match session.trainer_info_index {
                     ref mut opt @ None => *opt = Some(session.section_index),
                     Some(idx) => {
@@ -205,6 +210,11 @@ impl Section {
                 }
                 SectionData::TeamAndItems(TeamAndItems::read(reader, session)?)
             }
+            2..=4 => {
+                let index = id as usize - 2;
+                session.game_state_indexes[index] = Some(session.section_index);
+                SectionData::GameState(GameStateBuffer::read(reader, index)?)
+            }
             5..=13 => {
                 let index = id as usize - 5;
                 session.box_indexes[index] = session.section_index;
@@ -258,6 +268,7 @@ impl SectionData {
             SectionData::TrainerInfo(ref info) => info.write(writer),
             SectionData::TeamAndItems(ref data) => data.write(writer),
             SectionData::PcBuffer(ref data) => data.write(writer),
+            SectionData::GameState(ref data) => data.write(writer),
         }
     }
 }
@@ -346,20 +357,66 @@ impl<'a> Write for PokemonStorageWriter<'a> {
     }
 }
 
+type RawSectionData = [u8; DATA_SIZE as usize];
+
+/// Read the raw data of the trainer info and team/items sections of the block starting at the
+/// current position, and return to that position.
+fn peek_game_sections<R: Read + Seek>(
+    reader: &mut R,
+) -> Result<Option<(RawSectionData, RawSectionData)>, Box<dyn Error>> {
+    let block_pos = reader.stream_position()?;
+    let (mut trainer_info, mut team_and_items) = (None, None);
+    for _ in 0..14 {
+        let mut data = [0u8; DATA_SIZE as usize];
+        reader.read_exact(&mut data)?;
+        let id = reader.read_u16::<LE>()?;
+        // Skip checksum, unknown and save index
+        reader.seek(SeekFrom::Current(10))?;
+        match id {
+            0 => trainer_info = Some(data),
+            1 => team_and_items = Some(data),
+            _ => {}
+        }
+    }
+    reader.seek(SeekFrom::Start(block_pos))?;
+    Ok(trainer_info.zip(team_and_items))
+}
+
 impl SaveBlock {
-    fn read<R: Read + Seek>(reader: &mut R) -> Result<(Self, u32), Box<dyn Error>> {
+    fn read<R: Read + Seek>(
+        reader: &mut R,
+        forced: Option<GameType>,
+    ) -> Result<(Self, u32), Box<dyn Error>> {
         debug!("== Reading save block ==");
         let mut session = ReadSession::default();
+        let mut layout_confidence = Confidence::Forced;
+        if let Some(game_type) = forced {
+            session.game_type = Some(game_type);
+        } else if let Some((trainer_info, team_and_items)) = peek_game_sections(reader)? {
+            let (game_type, confidence) = version::detect_layout(&trainer_info, &team_and_items);
+            debug!("Detected layout {:?} ({:?})", game_type, confidence);
+            session.game_type = Some(game_type);
+            layout_confidence = confidence;
+        } else {
+            layout_confidence = Confidence::Low;
+        }
         let mut sections = Vec::new();
         for _ in 0..14 {
             sections.push(Section::read(reader, &mut session)?);
         }
         let (trainer_info_index, team_and_items_index, storage);
+        let mut game_state_indexes = [0; N_GAME_STATE_SECTIONS];
         if session.nonexistent {
             trainer_info_index = 0;
             team_and_items_index = 0;
             storage = Default::default();
         } else {
+            for (index, found) in game_state_indexes
+                .iter_mut()
+                .zip(session.game_state_indexes)
+            {
+                *index = found.ok_or("Missing game state section")?;
+            }
             trainer_info_index = session
                 .trainer_info_index
                 .ok_or("Missing TrainerInfo section")?;
@@ -375,8 +432,10 @@ impl SaveBlock {
                 trainer_info_index,
                 team_and_items_index,
                 nonexistent: session.nonexistent,
+                layout_confidence,
                 pokemon_storage: storage,
                 box_indexes: session.box_indexes,
+                game_state_indexes,
             },
             session.save_index.unwrap(),
         ))
@@ -402,11 +461,29 @@ impl SaveBlock {
 impl Save {
     /// Read the save data from a `Read` implementer.
     pub fn read<R: Read + Seek>(reader: &mut R) -> Result<Self, Box<dyn Error>> {
+        Self::read_impl(reader, None)
+    }
+    /// Read the save data from a `Read` implementer, skipping version detection.
+    pub fn read_as<R: Read + Seek>(
+        reader: &mut R,
+        version: GameVersion,
+    ) -> Result<Self, Box<dyn Error>> {
+        Self::read_impl(reader, Some(version))
+    }
+    fn read_impl<R: Read + Seek>(
+        reader: &mut R,
+        forced: Option<GameVersion>,
+    ) -> Result<Self, Box<dyn Error>> {
         debug!("== Reading save ==");
-        let (block1, block1_idx) = SaveBlock::read(reader)?;
-        let (block2, block2_idx) = SaveBlock::read(reader)?;
-        let mut unknown = [0; UNKNOWN_SAVE_FOOTER_SIZE];
-        reader.read_exact(&mut unknown)?;
+        let forced_type = forced.map(GameVersion::game_type);
+        let (block1, block1_idx) = SaveBlock::read(reader, forced_type)?;
+        let (block2, block2_idx) = SaveBlock::read(reader, forced_type)?;
+        let mut footer = Vec::with_capacity(N_FOOTER_SECTORS);
+        for _ in 0..N_FOOTER_SECTORS {
+            let mut data = [0; FOOTER_SECTOR_SIZE];
+            reader.read_exact(&mut data)?;
+            footer.push(FooterSector { data });
+        }
         let most_recent_index = if !block1.nonexistent && !block2.nonexistent {
             if block1_idx > block2_idx { 0 } else { 1 }
         } else if !block1.nonexistent && block2.nonexistent {
@@ -416,63 +493,129 @@ impl Save {
         } else {
             panic!("Both block 1 and block 2 do not exist.")
         };
-        Ok(Save {
+        let mut save = Save {
             blocks: [block1, block2],
-            unknown,
+            footer,
             most_recent_index,
-        })
+            version: DetectedVersion {
+                version: GameVersion::Emerald,
+                confidence: Confidence::Forced,
+            },
+        };
+        save.version = match forced {
+            Some(version) => DetectedVersion {
+                version,
+                confidence: Confidence::Forced,
+            },
+            None => save.refine_version(),
+        };
+        debug!("Game version: {:?}", save.version);
+        Ok(save)
+    }
+    /// Narrow down the detected layout to a specific game using the Pokémon the player
+    /// caught.
+    fn refine_version(&mut self) -> DetectedVersion {
+        let layout_confidence = self.blocks[self.most_recent_index].layout_confidence;
+        let SaveSections {
+            trainer,
+            team,
+            pc_boxes,
+        } = self.sections();
+        let game_type = GameType::from(&trainer.game);
+        let boxed = pc_boxes.iter().flat_map(|b| b.slots.iter().flatten());
+        let owned = team
+            .iter()
+            .chain(boxed)
+            .filter(|p| p.ot_id == trainer.full_id() && p.ot_name.same_as(&trainer.name));
+        let detected = version::refine_version(game_type, layout_confidence, owned);
+        if detected.version == GameVersion::Emerald && game_type == GameType::RubyOrSapphire {
+            // An Emerald save whose security key happens to be 0. The layout of the sections
+            // we parse is identical, only the interpretation of the game code differs.
+            for block in &mut self.blocks {
+                for section in &mut block.sections {
+                    if let SectionData::TrainerInfo(ref mut info) = section.data {
+                        info.game.reinterpret_as_emerald();
+                    }
+                }
+            }
+        }
+        detected
     }
     /// Write the save data to a `Write` implementer.
     pub fn write<W: Write>(&mut self, writer: &mut W) -> io::Result<()> {
         for block in &mut self.blocks {
             block.write(writer)?
         }
-        writer.write_all(&self.unknown)
+        for sector in &self.footer {
+            writer.write_all(&sector.data)?;
+        }
+        Ok(())
     }
 }
 
 impl Game {
-    fn read<R: Read>(reader: &mut R) -> Result<Self, Box<dyn Error>> {
-        Ok(match reader.read_u32::<LE>()? {
-            0 => {
+    fn read<R: Read>(reader: &mut R, game_type: GameType) -> Result<Self, Box<dyn Error>> {
+        let game_code = reader.read_u32::<LE>()?;
+        Ok(match game_type {
+            GameType::RubyOrSapphire => {
                 let mut trailing_data = [0; RS_EM_PLAYERINFO_TRAILING_DATA_SIZE];
                 reader.read_exact(&mut trailing_data)?;
-                Game::RubyOrSapphire { trailing_data }
+                Game::RubyOrSapphire {
+                    game_code,
+                    trailing_data,
+                }
             }
-            1 => {
+            GameType::FireredOrLeafgreen => {
                 let mut unknown = [0; FRLG_PLAYERINFO_UNKNOWN_CHUNK_SIZE];
                 reader.read_exact(&mut unknown)?;
                 let security_key = reader.read_u32::<LE>()?;
                 let mut trailing_data = [0; FRLG_PLAYERINFO_TRAILING_DATA_SIZE];
                 reader.read_exact(&mut trailing_data)?;
                 Game::FireredOrLeafgreen {
+                    game_code,
                     unknown,
                     security_key,
                     trailing_data,
                 }
             }
-            etc => {
+            GameType::Emerald => {
                 let mut trailing_data = [0; RS_EM_PLAYERINFO_TRAILING_DATA_SIZE];
                 reader.read_exact(&mut trailing_data)?;
                 Game::Emerald {
-                    security_key: etc,
+                    security_key: game_code,
                     trailing_data,
                 }
             }
         })
     }
+    fn reinterpret_as_emerald(&mut self) {
+        if let Game::RubyOrSapphire {
+            game_code,
+            trailing_data,
+        } = *self
+        {
+            *self = Game::Emerald {
+                security_key: game_code,
+                trailing_data,
+            };
+        }
+    }
     fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
         match *self {
-            Game::RubyOrSapphire { ref trailing_data } => {
-                writer.write_u32::<LE>(0)?;
+            Game::RubyOrSapphire {
+                game_code,
+                ref trailing_data,
+            } => {
+                writer.write_u32::<LE>(game_code)?;
                 writer.write_all(trailing_data)
             }
             Game::FireredOrLeafgreen {
+                game_code,
                 ref unknown,
                 security_key,
                 ref trailing_data,
             } => {
-                writer.write_u32::<LE>(1)?;
+                writer.write_u32::<LE>(game_code)?;
                 writer.write_all(unknown)?;
                 writer.write_u32::<LE>(security_key)?;
                 writer.write_all(trailing_data)
@@ -490,7 +633,9 @@ impl Game {
 
 impl TeamAndItems {
     fn read<R: Read>(reader: &mut R, session: &ReadSession) -> Result<Self, Box<dyn Error>> {
-        let game_type = session.game_type.expect("Game type not yet available");
+        let game_type = session
+            .game_type
+            .ok_or("Could not determine the game layout of the block")?;
         let unknown = TeamAndItemsUnknown::read(reader, game_type)?;
         let team_size = reader.read_u32::<LE>()?;
         debug!("Team size is {}", team_size);
@@ -584,7 +729,7 @@ impl TeamAndItemsRemaining {
 }
 
 impl Pokemon {
-    fn read_non_active<R: Read>(reader: &mut R) -> Result<Self, Box<dyn Error>> {
+    pub(crate) fn read_non_active<R: Read>(reader: &mut R) -> Result<Self, Box<dyn Error>> {
         let personality_value = reader.read_u32::<LE>()?;
         let ot_id = reader.read_u32::<LE>()?;
         let mut nick = [0; POKEMON_NICK_LEN];
@@ -603,7 +748,7 @@ impl Pokemon {
             rgen3_string::decode_string(&ot_name)
         );
         let data = PokemonData::read(reader, personality_value, ot_id)?;
-        Ok(Pokemon {
+        let mut pokemon = Pokemon {
             personality: personality_value,
             ot_id,
             nickname: PokemonNick(nick),
@@ -611,17 +756,20 @@ impl Pokemon {
             ot_name: TrainerName(ot_name),
             markings,
             _checksum: checksum,
+            bad_checksum: false,
             unknown_1,
             data,
             active_data: None,
-        })
+        };
+        pokemon.bad_checksum = pokemon.data_checksum()? != checksum;
+        Ok(pokemon)
     }
-    fn read<R: Read>(reader: &mut R) -> Result<Self, Box<dyn Error>> {
+    pub(crate) fn read<R: Read>(reader: &mut R) -> Result<Self, Box<dyn Error>> {
         let mut pokemon = Self::read_non_active(reader)?;
         pokemon.active_data = Some(PokemonActiveData::read(reader)?);
         Ok(pokemon)
     }
-    fn write_non_active<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+    pub(crate) fn write_non_active<W: Write>(&self, writer: &mut W) -> io::Result<()> {
         debug!("Writing data for Pokemon {:?}", self.nickname);
         writer.write_u32::<LE>(self.personality)?;
         writer.write_u32::<LE>(self.ot_id)?;
@@ -650,6 +798,13 @@ impl Pokemon {
             .expect("Requested full write on pokemon not having active data");
         active_data.write(writer)
     }
+    /// The checksum of the data, as it would be written.
+    pub(crate) fn data_checksum(&self) -> io::Result<u16> {
+        let mut data_buf = [0u8; POKEMON_DATA_LEN];
+        self.data
+            .write_unencrypted(&mut &mut data_buf[..], self.personality)?;
+        Pokemon::calc_data_checksum(&data_buf)
+    }
     fn calc_data_checksum(mut data: &[u8]) -> io::Result<u16> {
         let mut accum: u16 = 0;
         for _ in 0..POKEMON_DATA_LEN / 2 {
@@ -916,7 +1071,7 @@ impl PokemonActiveData {
         Ok(PokemonActiveData {
             status_condition: reader.read_u32::<LE>()?,
             level: reader.read_u8()?,
-            pokerus_remaining: reader.read_u8()?,
+            mail_id: reader.read_u8()?,
             current_hp: reader.read_u16::<LE>()?,
             total_hp: reader.read_u16::<LE>()?,
             attack: reader.read_u16::<LE>()?,
@@ -929,7 +1084,7 @@ impl PokemonActiveData {
     fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
         writer.write_u32::<LE>(self.status_condition)?;
         writer.write_u8(self.level)?;
-        writer.write_u8(self.pokerus_remaining)?;
+        writer.write_u8(self.mail_id)?;
         writer.write_u16::<LE>(self.current_hp)?;
         writer.write_u16::<LE>(self.total_hp)?;
         writer.write_u16::<LE>(self.attack)?;
@@ -940,6 +1095,26 @@ impl PokemonActiveData {
     }
 }
 
+impl GameStateBuffer {
+    fn read<R: Read>(reader: &mut R, index: usize) -> Result<Self, Box<dyn Error>> {
+        let mut data = [0u8; DATA_SIZE as usize];
+        reader.read_exact(&mut data)?;
+        Ok(GameStateBuffer { data, index })
+    }
+}
+
+impl SectionWrite for GameStateBuffer {
+    fn id(&self) -> u16 {
+        self.index as u16 + 2
+    }
+    fn cksum_area_len(&self) -> u64 {
+        if self.id() == 4 { 3848 } else { 3968 }
+    }
+    fn write_data<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&self.data)
+    }
+}
+
 impl PcBuffer {
     fn read<R: Read>(reader: &mut R, index: usize) -> Result<Self, Box<dyn Error>> {
         let mut data = [0u8; DATA_SIZE as usize];
@@ -1024,3 +1199,38 @@ impl PokeBox {
         Ok(())
     }
 }
+
+/// The raw bytes of a save whose sections are all zeroed, with the given section ids in each
+/// block.
+#[cfg(test)]
+fn blank_save_data(ids: [u16; 14]) -> Vec<u8> {
+    let mut data = Vec::new();
+    // The second block is the most recent one
+    for save_index in [0, 1] {
+        for id in ids {
+            data.extend_from_slice(&[0; DATA_SIZE as usize]);
+            data.write_u16::<LE>(id).unwrap();
+            data.extend_from_slice(&[0; 6]);
+            data.write_u32::<LE>(save_index).unwrap();
+        }
+    }
+    data.resize(data.len() + N_FOOTER_SECTORS * FOOTER_SECTOR_SIZE, 0);
+    data
+}
+
+/// A save whose sections are all zeroed, read as `version`.
+#[cfg(test)]
+pub(crate) fn blank_save(version: GameVersion) -> Save {
+    let ids = std::array::from_fn(|id| id as u16);
+    Save::read_as(&mut io::Cursor::new(blank_save_data(ids)), version).unwrap()
+}
+
+#[test]
+fn test_missing_game_state_section() {
+    let mut ids: [u16; 14] = std::array::from_fn(|id| id as u16);
+    // An unknown id in place of the last game state section
+    ids[4] = 14;
+    let data = blank_save_data(ids);
+    let err = Save::read_as(&mut io::Cursor::new(data), GameVersion::Emerald).unwrap_err();
+    assert_eq!(err.to_string(), "Missing game state section");
+}
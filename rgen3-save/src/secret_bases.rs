@@ -0,0 +1,223 @@
+//! Secret bases, Ruby/Sapphire/Emerald only.
+//!
+//! The first base is the player's own, the others are received through record mixing.
+
+use crate::{GameType, Gender, Save, TRAINER_NAME_LEN, TrainerName, UnsupportedGame};
+use byteorder::{ByteOrder, LittleEndian as LE};
+
+/// Size of a secret base, as exported by `SecretBase::raw`.
+pub const SECRET_BASE_SIZE: usize = 0xA0;
+pub const N_SECRET_BASES: usize = 20;
+/// Amount of decorations a secret base can hold.
+pub const SECRET_BASE_DECORATIONS: usize = 16;
+const PARTY_SIZE: usize = 6;
+
+const FLAGS_OFFSET: usize = 0x01;
+const NAME_OFFSET: usize = 0x02;
+const TRAINER_ID_OFFSET: usize = 0x09;
+const TIMES_ENTERED_OFFSET: usize = 0x10;
+const DECORATIONS_OFFSET: usize = 0x12;
+const DECORATION_POSITIONS_OFFSET: usize = 0x22;
+const PARTY_OFFSET: usize = 0x34;
+
+fn secret_bases_offset(game: GameType) -> Result<usize, UnsupportedGame> {
+    match game {
+        GameType::RubyOrSapphire => Ok(0x1A08),
+        GameType::Emerald => Ok(0x1A9C),
+        GameType::FireredOrLeafgreen => Err(UnsupportedGame),
+    }
+}
+
+/// A Pokémon the owner of a secret base battles with.
+#[derive(Clone, Copy, Debug)]
+pub struct SecretBaseMon {
+    pub personality: u32,
+    /// Species index.
+    pub species: u16,
+    pub held_item: u16,
+    pub moves: [u16; 4],
+    pub level: u8,
+    /// The same amount of EVs is used for every stat.
+    pub evs: u8,
+}
+
+/// A secret base, along with its owner and their party.
+#[derive(Clone)]
+pub struct SecretBase {
+    pub raw: [u8; SECRET_BASE_SIZE],
+}
+
+/// Error returned when importing a secret base fails.
+#[derive(Debug)]
+pub struct CantImportSecretBase;
+
+impl SecretBase {
+    /// Make a secret base out of its raw data, as exported by `raw`.
+    ///
+    /// Returns `None` if it's not the right size, or is not placed anywhere.
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        if data.len() != SECRET_BASE_SIZE || data[0] == 0 {
+            return None;
+        }
+        let mut raw = [0; SECRET_BASE_SIZE];
+        raw.copy_from_slice(data);
+        Some(SecretBase { raw })
+    }
+    /// Which of the secret base spots of the region this base is in.
+    pub fn location_id(&self) -> u8 {
+        self.raw[0]
+    }
+    pub fn owner_name(&self) -> TrainerName {
+        let mut name = TrainerName::default();
+        name.0
+            .copy_from_slice(&self.raw[NAME_OFFSET..NAME_OFFSET + TRAINER_NAME_LEN]);
+        name
+    }
+    pub fn owner_id(&self) -> u32 {
+        LE::read_u32(&self.raw[TRAINER_ID_OFFSET..])
+    }
+    pub fn owner_gender(&self) -> Gender {
+        if self.raw[FLAGS_OFFSET] & 0x10 == 0 {
+            Gender::Male
+        } else {
+            Gender::Female
+        }
+    }
+    /// The decorations placed in the base, with their positions as `(x << 4) | y`.
+    /// Empty slots have decoration 0.
+    pub fn decorations(&self) -> [(u8, u8); SECRET_BASE_DECORATIONS] {
+        let mut decorations = [(0, 0); SECRET_BASE_DECORATIONS];
+        for (i, decoration) in decorations.iter_mut().enumerate() {
+            *decoration = (
+                self.raw[DECORATIONS_OFFSET + i],
+                self.raw[DECORATION_POSITIONS_OFFSET + i],
+            );
+        }
+        decorations
+    }
+    /// The party of the owner, which the player can battle.
+    pub fn party(&self) -> Vec<SecretBaseMon> {
+        let party = &self.raw[PARTY_OFFSET..];
+        (0..PARTY_SIZE)
+            .map(|i| {
+                let mut moves = [0; 4];
+                LE::read_u16_into(&party[0x18 + i * 8..0x20 + i * 8], &mut moves);
+                SecretBaseMon {
+                    personality: LE::read_u32(&party[i * 4..]),
+                    species: LE::read_u16(&party[0x48 + i * 2..]),
+                    held_item: LE::read_u16(&party[0x54 + i * 2..]),
+                    moves,
+                    level: party[0x60 + i],
+                    evs: party[0x66 + i],
+                }
+            })
+            .filter(|mon| mon.species != 0)
+            .collect()
+    }
+    fn same_owner(&self, other: &SecretBase) -> bool {
+        self.owner_id() == other.owner_id() && self.owner_name().0 == other.owner_name().0
+    }
+}
+
+impl Save {
+    /// All the secret base slots, the player's own first. Empty slots are `None`.
+    pub fn secret_bases(&self) -> Result<Vec<Option<SecretBase>>, UnsupportedGame> {
+        let offset = secret_bases_offset(self.game_type())?;
+        let mut raw = vec![0; N_SECRET_BASES * SECRET_BASE_SIZE];
+        self.read_game_state(offset, &mut raw);
+        Ok(raw
+            .chunks(SECRET_BASE_SIZE)
+            .map(SecretBase::from_bytes)
+            .collect())
+    }
+    /// Overwrite the secret base slot at `index`, or empty it with `None`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not less than `N_SECRET_BASES`.
+    pub fn set_secret_base(
+        &mut self,
+        index: usize,
+        base: Option<&SecretBase>,
+    ) -> Result<(), UnsupportedGame> {
+        assert!(
+            index < N_SECRET_BASES,
+            "Invalid secret base slot: {}",
+            index
+        );
+        let offset = secret_bases_offset(self.game_type())?;
+        let raw = match base {
+            Some(base) => base.raw,
+            None => [0; SECRET_BASE_SIZE],
+        };
+        self.write_game_state(offset + index * SECRET_BASE_SIZE, &raw);
+        Ok(())
+    }
+    /// Add a secret base from another save, like record mixing does.
+    ///
+    /// A base from the same owner or at the same spot is replaced, otherwise the first empty
+    /// slot is used. The base is marked as unregistered and never visited.
+    ///
+    /// Returns the slot the base was put in. Fails if the player's own base is at the same
+    /// spot or from the same owner, or there is no room left.
+    pub fn import_secret_base(&mut self, base: &SecretBase) -> Result<usize, CantImportSecretBase> {
+        let bases = self.secret_bases().map_err(|_| CantImportSecretBase)?;
+        if let Some(ref own) = bases[0]
+            && (own.location_id() == base.location_id() || own.same_owner(base))
+        {
+            return Err(CantImportSecretBase);
+        }
+        let slot = (1..N_SECRET_BASES)
+            .find(|&i| bases[i].as_ref().is_some_and(|b| b.same_owner(base)))
+            .or_else(|| {
+                (1..N_SECRET_BASES).find(|&i| {
+                    bases[i]
+                        .as_ref()
+                        .is_some_and(|b| b.location_id() == base.location_id())
+                })
+            })
+            .or_else(|| (1..N_SECRET_BASES).find(|&i| bases[i].is_none()))
+            .ok_or(CantImportSecretBase)?;
+        let mut base = base.clone();
+        // Only keep the gender out of the flags
+        base.raw[FLAGS_OFFSET] &= 0x10;
+        base.raw[TIMES_ENTERED_OFFSET] = 0;
+        self.set_secret_base(slot, Some(&base))
+            .map_err(|_| CantImportSecretBase)?;
+        Ok(slot)
+    }
+}
+
+#[test]
+fn test_import_secret_base() {
+    use crate::GameVersion;
+    let mut save = crate::rw::blank_save(GameVersion::Emerald);
+    let base = |location: u8, owner_id: u32| {
+        let mut raw = [0; SECRET_BASE_SIZE];
+        raw[0] = location;
+        raw[FLAGS_OFFSET] = 0x13;
+        raw[TIMES_ENTERED_OFFSET] = 5;
+        LE::write_u32(&mut raw[TRAINER_ID_OFFSET..], owner_id);
+        LE::write_u16(&mut raw[PARTY_OFFSET + 0x48..], 25);
+        SecretBase::from_bytes(&raw).unwrap()
+    };
+    assert!(SecretBase::from_bytes(&[0; SECRET_BASE_SIZE]).is_none());
+    save.set_secret_base(0, Some(&base(1, 1))).unwrap();
+    // Same spot as the player's base
+    assert!(save.import_secret_base(&base(1, 2)).is_err());
+    assert_eq!(save.import_secret_base(&base(2, 2)).unwrap(), 1);
+    assert_eq!(save.import_secret_base(&base(3, 3)).unwrap(), 2);
+    // Same owner, moved to another spot
+    assert_eq!(save.import_secret_base(&base(4, 2)).unwrap(), 1);
+    let bases = save.secret_bases().unwrap();
+    let imported = bases[1].as_ref().unwrap();
+    assert_eq!(imported.location_id(), 4);
+    assert_eq!(imported.owner_gender(), Gender::Female);
+    assert_eq!(imported.raw[FLAGS_OFFSET], 0x10);
+    assert_eq!(imported.raw[TIMES_ENTERED_OFFSET], 0);
+    assert_eq!(imported.party()[0].species, 25);
+    assert!(bases[3].is_none());
+    let mut save = crate::rw::blank_save(GameVersion::FireRed);
+    assert!(save.secret_bases().is_err());
+    assert!(save.import_secret_base(&base(2, 2)).is_err());
+}
@@ -0,0 +1,165 @@
+//! Data about species, like base stats, evolutions and learnsets.
+//!
+//! This is game data that is not part of the save, so it has to be provided by the user,
+//! species by species.
+
+use crate::{GameType, GameVersion};
+use std::collections::HashMap;
+
+/// How much experience a species needs to level up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GrowthRate {
+    MediumFast,
+    Erratic,
+    Fluctuating,
+    MediumSlow,
+    Fast,
+    Slow,
+}
+
+impl GrowthRate {
+    /// The experience needed to reach `level`.
+    pub fn experience(self, level: u8) -> u32 {
+        let n = i64::from(level.clamp(1, 100));
+        let cube = n * n * n;
+        let exp = match self {
+            GrowthRate::MediumFast => cube,
+            GrowthRate::Erratic => match n {
+                ..=50 => cube * (100 - n) / 50,
+                51..=68 => cube * (150 - n) / 100,
+                69..=98 => cube * ((1911 - 10 * n) / 3) / 500,
+                _ => cube * (160 - n) / 100,
+            },
+            GrowthRate::Fluctuating => match n {
+                ..=15 => cube * ((n + 1) / 3 + 24) / 50,
+                16..=36 => cube * (n + 14) / 50,
+                _ => cube * (n / 2 + 32) / 50,
+            },
+            GrowthRate::MediumSlow => 6 * cube / 5 - 15 * n * n + 100 * n - 140,
+            GrowthRate::Fast => 4 * cube / 5,
+            GrowthRate::Slow => 5 * cube / 4,
+        };
+        if n == 1 { 0 } else { exp as u32 }
+    }
+    /// The level a Pokémon with `experience` is at.
+    pub fn level(self, experience: u32) -> u8 {
+        (2..=100)
+            .take_while(|&level| self.experience(level) <= experience)
+            .last()
+            .unwrap_or(1)
+    }
+}
+
+/// How a species evolves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EvolutionMethod {
+    /// Leveling up with a friendship of 220 or more.
+    Friendship,
+    FriendshipDay,
+    FriendshipNight,
+    /// Reaching the level.
+    Level(u8),
+    Trade,
+    /// Being traded while holding the item.
+    TradeItem(u16),
+    /// Using the item on it.
+    Item(u16),
+    /// Reaching the level with more Attack than Defense.
+    LevelAttackHigher(u8),
+    /// Reaching the level with as much Attack as Defense.
+    LevelAttackEqual(u8),
+    /// Reaching the level with more Defense than Attack.
+    LevelDefenseHigher(u8),
+    /// Reaching the level with the personality picking Silcoon.
+    LevelSilcoon(u8),
+    /// Reaching the level with the personality picking Cascoon.
+    LevelCascoon(u8),
+    /// Reaching the level, for Nincada becoming Ninjask.
+    LevelNinjask(u8),
+    /// Reaching the level, for the Shedinja left behind when Nincada evolves.
+    LevelShedinja(u8),
+    /// Leveling up with at least this much Beauty.
+    Beauty(u8),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Evolution {
+    pub method: EvolutionMethod,
+    /// The species evolved into.
+    pub target: u16,
+}
+
+/// The moves a species can learn in a game.
+#[derive(Clone, Debug, Default)]
+pub struct Learnset {
+    /// Moves learned by leveling up, with the level they are learned at.
+    pub level_up: Vec<(u8, u16)>,
+    pub tm_hm: Vec<u16>,
+    /// Moves taught by move tutors.
+    pub tutor: Vec<u16>,
+    /// Moves the species can hatch with.
+    pub egg: Vec<u16>,
+}
+
+/// The learnsets of a species in each game, as the TMs and move tutors are not the same in
+/// every game.
+#[derive(Clone, Debug, Default)]
+pub struct Learnsets {
+    by_game: HashMap<GameType, Learnset>,
+}
+
+impl Learnsets {
+    /// Set the learnset of `game`. Ruby and Sapphire share theirs, as do Fire Red and Leaf
+    /// Green.
+    pub fn insert(&mut self, game: GameVersion, learnset: Learnset) {
+        self.by_game.insert(game.game_type(), learnset);
+    }
+    pub fn get(&self, game: GameVersion) -> Option<&Learnset> {
+        self.by_game.get(&game.game_type())
+    }
+    /// The learnsets of every game there is one for.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &Learnset> {
+        self.by_game.values()
+    }
+}
+
+/// What is known about a species.
+#[derive(Clone, Debug)]
+pub struct SpeciesInfo {
+    /// HP, Attack, Defense, Speed, Special Attack and Special Defense.
+    pub base_stats: [u8; 6],
+    pub growth_rate: GrowthRate,
+    /// How many egg cycles an egg of this species takes to hatch.
+    pub egg_cycles: u8,
+    pub evolutions: Vec<Evolution>,
+    pub learnsets: Learnsets,
+}
+
+/// Data about the species, by species index.
+#[derive(Clone, Debug, Default)]
+pub struct SpeciesTable {
+    species: HashMap<u16, SpeciesInfo>,
+}
+
+impl SpeciesTable {
+    pub fn insert(&mut self, species: u16, info: SpeciesInfo) {
+        self.species.insert(species, info);
+    }
+    pub fn get(&self, species: u16) -> Option<&SpeciesInfo> {
+        self.species.get(&species)
+    }
+    /// The species that evolve into `species`, directly or not.
+    pub(crate) fn pre_evolutions(&self, species: u16) -> Vec<u16> {
+        let mut found = Vec::new();
+        let mut targets = vec![species];
+        while let Some(target) = targets.pop() {
+            for (&from, info) in &self.species {
+                if !found.contains(&from) && info.evolutions.iter().any(|e| e.target == target) {
+                    found.push(from);
+                    targets.push(from);
+                }
+            }
+        }
+        found
+    }
+}
@@ -0,0 +1,231 @@
+//! Game statistics counters, like steps taken or eggs hatched.
+//!
+//! Emerald and Fire Red/Leaf Green xor every counter with the security key.
+
+use crate::{GameType, Save};
+use byteorder::{ByteOrder, LittleEndian as LE};
+use std::fmt;
+
+/// Offset of the statistics in the game state, and their amount.
+fn stats_location(game: GameType) -> (usize, usize) {
+    match game {
+        GameType::RubyOrSapphire => (0x1540, 50),
+        GameType::Emerald => (0x159C, 64),
+        GameType::FireredOrLeafgreen => (0x1200, 64),
+    }
+}
+
+/// Index of a game statistic.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct GameStat(pub u8);
+
+static STAT_NAMES: [&str; 52] = [
+    "Saved game",
+    "First Hall of Fame play time",
+    "Started trends",
+    "Planted berries",
+    "Traded bikes",
+    "Steps",
+    "Got interviewed",
+    "Total battles",
+    "Wild battles",
+    "Trainer battles",
+    "Entered Hall of Fame",
+    "Pokémon captures",
+    "Fishing captures",
+    "Hatched eggs",
+    "Evolved Pokémon",
+    "Used Pokémon Center",
+    "Rested at home",
+    "Entered Safari Zone",
+    "Used Cut",
+    "Used Rock Smash",
+    "Moved secret base",
+    "Pokémon trades",
+    "Unknown (22)",
+    "Link battle wins",
+    "Link battle losses",
+    "Link battle draws",
+    "Used Splash",
+    "Used Struggle",
+    "Slot jackpots",
+    "Consecutive roulette wins",
+    "Entered Battle Tower",
+    "Unknown (31)",
+    "Battle Tower best streak",
+    "Pokéblocks",
+    "Pokéblocks with friends",
+    "Won link contest",
+    "Entered contest",
+    "Won contest",
+    "Shopped",
+    "Used Itemfinder",
+    "Got rained on",
+    "Checked Pokédex",
+    "Received ribbons",
+    "Jumped down ledges",
+    "Watched TV",
+    "Checked clock",
+    "Won Pokémon lottery",
+    "Used Day Care",
+    "Rode cable car",
+    "Entered hot springs",
+    "Union Room battles",
+    "Played Berry Crush",
+];
+
+#[allow(missing_docs)]
+impl GameStat {
+    pub const SAVED_GAME: Self = GameStat(0);
+    pub const FIRST_HOF_PLAY_TIME: Self = GameStat(1);
+    pub const STARTED_TRENDS: Self = GameStat(2);
+    pub const PLANTED_BERRIES: Self = GameStat(3);
+    pub const TRADED_BIKES: Self = GameStat(4);
+    pub const STEPS: Self = GameStat(5);
+    pub const GOT_INTERVIEWED: Self = GameStat(6);
+    pub const TOTAL_BATTLES: Self = GameStat(7);
+    pub const WILD_BATTLES: Self = GameStat(8);
+    pub const TRAINER_BATTLES: Self = GameStat(9);
+    pub const ENTERED_HOF: Self = GameStat(10);
+    pub const POKEMON_CAPTURES: Self = GameStat(11);
+    pub const FISHING_CAPTURES: Self = GameStat(12);
+    pub const HATCHED_EGGS: Self = GameStat(13);
+    pub const EVOLVED_POKEMON: Self = GameStat(14);
+    pub const USED_POKECENTER: Self = GameStat(15);
+    pub const RESTED_AT_HOME: Self = GameStat(16);
+    pub const ENTERED_SAFARI_ZONE: Self = GameStat(17);
+    pub const USED_CUT: Self = GameStat(18);
+    pub const USED_ROCK_SMASH: Self = GameStat(19);
+    pub const MOVED_SECRET_BASE: Self = GameStat(20);
+    pub const POKEMON_TRADES: Self = GameStat(21);
+    pub const LINK_BATTLE_WINS: Self = GameStat(23);
+    pub const LINK_BATTLE_LOSSES: Self = GameStat(24);
+    pub const LINK_BATTLE_DRAWS: Self = GameStat(25);
+    pub const USED_SPLASH: Self = GameStat(26);
+    pub const USED_STRUGGLE: Self = GameStat(27);
+    pub const SLOT_JACKPOTS: Self = GameStat(28);
+    pub const CONSECUTIVE_ROULETTE_WINS: Self = GameStat(29);
+    pub const ENTERED_BATTLE_TOWER: Self = GameStat(30);
+    pub const BATTLE_TOWER_BEST_STREAK: Self = GameStat(32);
+    pub const POKEBLOCKS: Self = GameStat(33);
+    pub const POKEBLOCKS_WITH_FRIENDS: Self = GameStat(34);
+    pub const WON_LINK_CONTEST: Self = GameStat(35);
+    pub const ENTERED_CONTEST: Self = GameStat(36);
+    pub const WON_CONTEST: Self = GameStat(37);
+    pub const SHOPPED: Self = GameStat(38);
+    pub const USED_ITEMFINDER: Self = GameStat(39);
+    pub const GOT_RAINED_ON: Self = GameStat(40);
+    pub const CHECKED_POKEDEX: Self = GameStat(41);
+    pub const RECEIVED_RIBBONS: Self = GameStat(42);
+    pub const JUMPED_DOWN_LEDGES: Self = GameStat(43);
+    pub const WATCHED_TV: Self = GameStat(44);
+    pub const CHECKED_CLOCK: Self = GameStat(45);
+    pub const WON_POKEMON_LOTTERY: Self = GameStat(46);
+    pub const USED_DAYCARE: Self = GameStat(47);
+    pub const RODE_CABLE_CAR: Self = GameStat(48);
+    pub const ENTERED_HOT_SPRINGS: Self = GameStat(49);
+    /// Emerald and Fire Red/Leaf Green only.
+    pub const UNION_ROOM_BATTLES: Self = GameStat(50);
+    /// Emerald and Fire Red/Leaf Green only.
+    pub const PLAYED_BERRY_CRUSH: Self = GameStat(51);
+}
+
+impl GameStat {
+    /// A human readable name for the statistic.
+    pub fn name(self) -> &'static str {
+        STAT_NAMES
+            .get(usize::from(self.0))
+            .cloned()
+            .unwrap_or("Unused")
+    }
+}
+
+impl fmt::Display for GameStat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A decrypted copy of the game statistics of a save.
+///
+/// Get it with `Save::game_stats`, and write it back with `Save::set_game_stats`.
+#[derive(Clone, Debug)]
+pub struct GameStats {
+    values: Vec<u32>,
+}
+
+impl GameStats {
+    /// Number of counters in this game. Some of them are unused.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+    /// # Panics
+    ///
+    /// Panics if the statistic doesn't exist in this game.
+    pub fn get(&self, stat: GameStat) -> u32 {
+        self.values[usize::from(stat.0)]
+    }
+    /// # Panics
+    ///
+    /// Panics if the statistic doesn't exist in this game.
+    pub fn set(&mut self, stat: GameStat, value: u32) {
+        self.values[usize::from(stat.0)] = value;
+    }
+    /// Every counter, along with its value.
+    pub fn iter(&self) -> impl Iterator<Item = (GameStat, u32)> + '_ {
+        self.values
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| (GameStat(i as u8), value))
+    }
+}
+
+impl Save {
+    pub fn game_stats(&self) -> GameStats {
+        let (offset, len) = stats_location(self.game_type());
+        let mut raw = vec![0; len * 4];
+        self.read_game_state(offset, &mut raw);
+        let key = self.sections().trainer.security_key();
+        let values = raw.chunks(4).map(|v| LE::read_u32(v) ^ key).collect();
+        GameStats { values }
+    }
+    /// # Panics
+    ///
+    /// Panics if `stats` was not taken from a save of the same game.
+    pub fn set_game_stats(&mut self, stats: &GameStats) {
+        let (offset, len) = stats_location(self.game_type());
+        assert_eq!(stats.len(), len, "Game stats are from a different game");
+        let key = self.sections().trainer.security_key();
+        let mut raw = vec![0; len * 4];
+        for (dst, value) in raw.chunks_mut(4).zip(&stats.values) {
+            LE::write_u32(dst, value ^ key);
+        }
+        self.write_game_state(offset, &raw);
+    }
+}
+
+#[test]
+fn test_stats_encryption() {
+    use crate::{Game, GameVersion};
+    let mut save = crate::rw::blank_save(GameVersion::Emerald);
+    let key = 0x1234_5678;
+    if let Game::Emerald {
+        ref mut security_key,
+        ..
+    } = save.sections_mut().trainer.game
+    {
+        *security_key = key;
+    }
+    let mut stats = save.game_stats();
+    assert_eq!(stats.len(), 64);
+    assert_eq!(stats.get(GameStat::SAVED_GAME), key);
+    stats.set(GameStat::SAVED_GAME, 3);
+    save.set_game_stats(&stats);
+    let mut raw = [0; 4];
+    save.read_game_state(stats_location(GameType::Emerald).0, &mut raw);
+    assert_eq!(LE::read_u32(&raw), 3 ^ key);
+    assert_eq!(save.game_stats().get(GameStat::SAVED_GAME), 3);
+}
@@ -0,0 +1,104 @@
+//! The TV shows made from interviews of the player, with the easy chat words they answered.
+//! Ruby/Sapphire/Emerald only.
+//!
+//! The other shows are made from what the player did, or mixed in from the saves of other
+//! players, and hold no words.
+
+use crate::easy_chat::{EasyChatWord, WordTable};
+use crate::{GameType, Save, UnsupportedGame};
+use byteorder::{ByteOrder, LittleEndian as LE};
+
+const N_TV_SHOWS: usize = 25;
+const TV_SHOW_SIZE: usize = 36;
+
+fn tv_shows_offset(game: GameType) -> Result<usize, UnsupportedGame> {
+    match game {
+        GameType::RubyOrSapphire => Ok(0x2738),
+        GameType::Emerald => Ok(0x27CC),
+        GameType::FireredOrLeafgreen => Err(UnsupportedGame),
+    }
+}
+
+/// The shows the player is interviewed for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TvInterviewKind {
+    /// A letter to the Pokémon Fan Club about a Pokémon.
+    FanClubLetter,
+    /// Something that happened to the player lately.
+    RecentHappenings,
+    /// What the player thinks of their Pokémon, asked at the Pokémon Fan Club.
+    FanClubOpinions,
+    /// Bravo Trainer, about a Pokémon after a contest.
+    BravoTrainer,
+    /// Bravo Trainer, about the player's challenge of the Battle Tower.
+    BattleTower,
+}
+
+impl TvInterviewKind {
+    /// The interview of a show of kind `kind`, with where its words are and how many.
+    fn of_show(kind: u8) -> Option<(Self, usize, usize)> {
+        match kind {
+            1 => Some((TvInterviewKind::FanClubLetter, 0x04, 6)),
+            2 => Some((TvInterviewKind::RecentHappenings, 0x04, 6)),
+            3 => Some((TvInterviewKind::FanClubOpinions, 0x1C, 2)),
+            6 => Some((TvInterviewKind::BravoTrainer, 0x04, 2)),
+            7 => Some((TvInterviewKind::BattleTower, 0x18, 1)),
+            _ => None,
+        }
+    }
+}
+
+/// A TV show the player was interviewed for.
+#[derive(Clone, Debug)]
+pub struct TvInterview {
+    pub kind: TvInterviewKind,
+    /// Whether the show is on air.
+    pub active: bool,
+    /// The words the player answered with.
+    pub words: Vec<EasyChatWord>,
+}
+
+impl TvInterview {
+    fn read(data: &[u8]) -> Option<Self> {
+        let (kind, offset, n_words) = TvInterviewKind::of_show(data[0])?;
+        let words = (0..n_words)
+            .map(|i| EasyChatWord(LE::read_u16(&data[offset + i * 2..])))
+            .collect();
+        Some(TvInterview {
+            kind,
+            active: data[1] != 0,
+            words,
+        })
+    }
+    /// The answers, with the words looked up in `table`.
+    pub fn text(&self, table: &WordTable) -> String {
+        table.decode(&self.words)
+    }
+}
+
+impl Save {
+    /// The TV shows the player was interviewed for, on air or not.
+    pub fn tv_interviews(&self) -> Result<Vec<TvInterview>, UnsupportedGame> {
+        let offset = tv_shows_offset(self.game_type())?;
+        let mut raw = [0; N_TV_SHOWS * TV_SHOW_SIZE];
+        self.read_game_state(offset, &mut raw);
+        Ok(raw
+            .chunks(TV_SHOW_SIZE)
+            .filter_map(TvInterview::read)
+            .collect())
+    }
+}
+
+#[test]
+fn test_read_tv_interview() {
+    let mut data = [0; TV_SHOW_SIZE];
+    assert!(TvInterview::read(&data).is_none());
+    data[0] = 3;
+    data[1] = 1;
+    LE::write_u16(&mut data[0x1C..], EasyChatWord::new(0, 25).0);
+    LE::write_u16(&mut data[0x1E..], EasyChatWord::EMPTY.0);
+    let interview = TvInterview::read(&data).unwrap();
+    assert_eq!(interview.kind, TvInterviewKind::FanClubOpinions);
+    assert!(interview.active);
+    assert_eq!(interview.text(&WordTable::default()), "Pikachu");
+}
@@ -0,0 +1,269 @@
+//! Detection of which game a save file was made with.
+
+use crate::{
+    EM_RU_SA_TEAMANDITEMS_UNK_LEN, FR_LG_TEAMANDITEMS_UNK_LEN, FRLG_PLAYERINFO_UNKNOWN_CHUNK_SIZE,
+    GameType, Pokemon, TEAMANDITEMS_POKE_LEN,
+};
+use byteorder::{ByteOrder, LittleEndian as LE};
+
+/// A Gen3 game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameVersion {
+    Ruby,
+    Sapphire,
+    /// Ruby or Sapphire, but the save doesn't contain enough information to tell which.
+    RubyOrSapphire,
+    Emerald,
+    FireRed,
+    LeafGreen,
+    /// Fire Red or Leaf Green, but the save doesn't contain enough information to tell which.
+    FireRedOrLeafGreen,
+}
+
+impl GameVersion {
+    /// The code identifying this game in a Pokémon's origin info.
+    pub(crate) fn origin_game_code(self) -> u16 {
+        match self {
+            GameVersion::Sapphire => 1,
+            GameVersion::Ruby => 2,
+            GameVersion::Emerald => 3,
+            GameVersion::FireRed => 4,
+            GameVersion::LeafGreen => 5,
+            GameVersion::RubyOrSapphire | GameVersion::FireRedOrLeafGreen => 0,
+        }
+    }
+    pub(crate) fn game_type(self) -> GameType {
+        match self {
+            GameVersion::Ruby | GameVersion::Sapphire | GameVersion::RubyOrSapphire => {
+                GameType::RubyOrSapphire
+            }
+            GameVersion::Emerald => GameType::Emerald,
+            GameVersion::FireRed | GameVersion::LeafGreen | GameVersion::FireRedOrLeafGreen => {
+                GameType::FireredOrLeafgreen
+            }
+        }
+    }
+}
+
+/// How sure the detection is about a `DetectedVersion`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Confidence {
+    /// The save data contradicts every known layout, the version was guessed from the
+    /// game code alone.
+    Low,
+    /// More than one version fits the save data, the most likely one was chosen.
+    Medium,
+    /// Every signal agrees.
+    High,
+    /// The version was chosen by the caller when loading.
+    Forced,
+}
+
+/// Game version of a save, along with how it was determined.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DetectedVersion {
+    pub version: GameVersion,
+    pub confidence: Confidence,
+}
+
+const GAME_CODE_OFFSET: usize = 0xAC;
+const FRLG_SECURITY_KEY_OFFSET: usize = GAME_CODE_OFFSET + 4 + FRLG_PLAYERINFO_UNKNOWN_CHUNK_SIZE;
+const MAX_MONEY: u32 = 999_999;
+const MAX_COINS: u16 = 9_999;
+const MAX_TEAM_SIZE: u32 = 6;
+
+/// Count how many consistency checks fail when reading the raw trainer info (`sec0`) and
+/// team/items (`sec1`) sections as `game_type`.
+fn n_failed_checks(sec0: &[u8], sec1: &[u8], game_type: GameType) -> u32 {
+    let game_code = LE::read_u32(&sec0[GAME_CODE_OFFSET..]);
+    let (team_offset, security_key, expected_code) = match game_type {
+        GameType::RubyOrSapphire => (EM_RU_SA_TEAMANDITEMS_UNK_LEN, 0, Some(0)),
+        GameType::Emerald => (EM_RU_SA_TEAMANDITEMS_UNK_LEN, game_code, None),
+        GameType::FireredOrLeafgreen => (
+            FR_LG_TEAMANDITEMS_UNK_LEN,
+            LE::read_u32(&sec0[FRLG_SECURITY_KEY_OFFSET..]),
+            Some(1),
+        ),
+    };
+    // Money and coins come right after the team, and are xored with the security key
+    let money_offset = team_offset + 4 + TEAMANDITEMS_POKE_LEN;
+    let team_size = LE::read_u32(&sec1[team_offset..]);
+    let money = LE::read_u32(&sec1[money_offset..]) ^ security_key;
+    let coins = LE::read_u16(&sec1[money_offset + 4..]) ^ security_key as u16;
+    let checks = [
+        expected_code.is_none_or(|code| code == game_code),
+        team_size <= MAX_TEAM_SIZE,
+        money <= MAX_MONEY,
+        coins <= MAX_COINS,
+    ];
+    checks.iter().filter(|&&ok| !ok).count() as u32
+}
+
+/// Figure out the layout of a save block from its raw trainer info and team/items sections.
+pub(crate) fn detect_layout(sec0: &[u8], sec1: &[u8]) -> (GameType, Confidence) {
+    let candidates = [
+        GameType::RubyOrSapphire,
+        GameType::FireredOrLeafgreen,
+        GameType::Emerald,
+    ];
+    let passing: Vec<GameType> = candidates
+        .iter()
+        .cloned()
+        .filter(|&game_type| n_failed_checks(sec0, sec1, game_type) == 0)
+        .collect();
+    // The game code is 0 for Ruby/Sapphire, 1 for Fire Red/Leaf Green, and the security
+    // key for Emerald. It's only trustworthy if the rest of the data agrees.
+    let by_game_code = match LE::read_u32(&sec0[GAME_CODE_OFFSET..]) {
+        0 => GameType::RubyOrSapphire,
+        1 => GameType::FireredOrLeafgreen,
+        _ => GameType::Emerald,
+    };
+    match passing[..] {
+        [game_type] => (game_type, Confidence::High),
+        [] => (by_game_code, Confidence::Low),
+        _ if passing.contains(&by_game_code) => (by_game_code, Confidence::Medium),
+        _ => (passing[0], Confidence::Medium),
+    }
+}
+
+/// Pick the specific version for a save laid out as `game_type`, using the origin game of
+/// the Pokémon the player caught themselves as hints.
+pub(crate) fn refine_version<'a, I>(
+    game_type: GameType,
+    layout_confidence: Confidence,
+    owned_pokemon: I,
+) -> DetectedVersion
+where
+    I: IntoIterator<Item = &'a Pokemon>,
+{
+    let mut votes = [0u32; 16];
+    for pokemon in owned_pokemon {
+        votes[usize::from(pokemon.data.misc.origin_game())] += 1;
+    }
+    let vote = |version: GameVersion| votes[usize::from(version.origin_game_code())];
+    let (candidates, fallback): (&[GameVersion], GameVersion) = match game_type {
+        // Emerald with a security key of 0 looks exactly like Ruby/Sapphire
+        GameType::RubyOrSapphire => (
+            &[
+                GameVersion::Ruby,
+                GameVersion::Sapphire,
+                GameVersion::Emerald,
+            ],
+            GameVersion::RubyOrSapphire,
+        ),
+        GameType::FireredOrLeafgreen => (
+            &[GameVersion::FireRed, GameVersion::LeafGreen],
+            GameVersion::FireRedOrLeafGreen,
+        ),
+        GameType::Emerald => {
+            return DetectedVersion {
+                version: GameVersion::Emerald,
+                confidence: layout_confidence,
+            };
+        }
+    };
+    let total: u32 = candidates.iter().map(|&v| vote(v)).sum();
+    let best = candidates.iter().cloned().max_by_key(|&v| vote(v)).unwrap();
+    let runner_up = candidates
+        .iter()
+        .filter(|&&v| v != best)
+        .map(|&v| vote(v))
+        .max()
+        .unwrap_or(0);
+    if total == 0 || vote(best) == runner_up {
+        return DetectedVersion {
+            version: fallback,
+            confidence: layout_confidence,
+        };
+    }
+    let confidence = if vote(best) == total {
+        layout_confidence
+    } else {
+        layout_confidence.min(Confidence::Medium)
+    };
+    DetectedVersion {
+        version: best,
+        confidence,
+    }
+}
+
+#[test]
+fn test_detect_layout() {
+    use crate::DATA_SIZE;
+    let (mut sec0, mut sec1) = ([0; DATA_SIZE as usize], [0; DATA_SIZE as usize]);
+    // A blank Emerald save with a security key of 0 can't be told apart from Ruby/Sapphire
+    assert_eq!(
+        detect_layout(&sec0, &sec1),
+        (GameType::RubyOrSapphire, Confidence::Medium)
+    );
+    // Fire Red/Leaf Green, with the money xored with the security key
+    let key = 0x1234_5678;
+    LE::write_u32(&mut sec0[GAME_CODE_OFFSET..], 1);
+    LE::write_u32(&mut sec0[FRLG_SECURITY_KEY_OFFSET..], key);
+    let money_offset = FR_LG_TEAMANDITEMS_UNK_LEN + 4 + TEAMANDITEMS_POKE_LEN;
+    LE::write_u32(&mut sec1[money_offset..], 3000 ^ key);
+    LE::write_u16(&mut sec1[money_offset + 4..], key as u16);
+    assert_eq!(
+        detect_layout(&sec0, &sec1),
+        (GameType::FireredOrLeafgreen, Confidence::Medium)
+    );
+    // The items that follow are too much money for Emerald
+    let emerald_money_offset = EM_RU_SA_TEAMANDITEMS_UNK_LEN + 4 + TEAMANDITEMS_POKE_LEN;
+    LE::write_u32(&mut sec1[emerald_money_offset..], 0xFFFF_FFFF);
+    assert_eq!(
+        detect_layout(&sec0, &sec1),
+        (GameType::FireredOrLeafgreen, Confidence::High)
+    );
+    // Emerald, the game code being the security key
+    let (mut sec0, mut sec1) = ([0; DATA_SIZE as usize], [0; DATA_SIZE as usize]);
+    LE::write_u32(&mut sec0[GAME_CODE_OFFSET..], key);
+    LE::write_u32(&mut sec1[emerald_money_offset..], 3000 ^ key);
+    LE::write_u16(&mut sec1[emerald_money_offset + 4..], key as u16);
+    assert_eq!(
+        detect_layout(&sec0, &sec1),
+        (GameType::Emerald, Confidence::High)
+    );
+    // Nothing fits, the game code decides
+    LE::write_u32(&mut sec1[EM_RU_SA_TEAMANDITEMS_UNK_LEN..], 7);
+    assert_eq!(
+        detect_layout(&sec0, &sec1),
+        (GameType::Emerald, Confidence::Low)
+    );
+}
+
+#[test]
+fn test_refine_version() {
+    let caught_in = |origin_game: u16| {
+        let mut pokemon = Pokemon::default();
+        pokemon.data.misc.origins_info = origin_game << 7;
+        pokemon
+    };
+    let owned = [caught_in(2), caught_in(2), caught_in(1)];
+    let detected = refine_version(GameType::RubyOrSapphire, Confidence::High, &owned);
+    assert_eq!(detected.version, GameVersion::Ruby);
+    assert_eq!(detected.confidence, Confidence::Medium);
+    let detected = refine_version(GameType::RubyOrSapphire, Confidence::High, &owned[1..]);
+    assert_eq!(detected.version, GameVersion::RubyOrSapphire);
+    let owned = [caught_in(5)];
+    let detected = refine_version(GameType::FireredOrLeafgreen, Confidence::High, &owned);
+    assert_eq!(
+        detected,
+        DetectedVersion {
+            version: GameVersion::LeafGreen,
+            confidence: Confidence::High,
+        }
+    );
+}
+
+#[test]
+fn test_forced_version() {
+    let save = crate::rw::blank_save(GameVersion::FireRed);
+    assert_eq!(
+        save.game_version(),
+        DetectedVersion {
+            version: GameVersion::FireRed,
+            confidence: Confidence::Forced,
+        }
+    );
+    assert_eq!(save.game_type(), GameType::FireredOrLeafgreen);
+}
@@ -48,6 +48,7 @@ map! {
     0xA1 => P('0'), 0xA2 => P('1'), 0xA3 => P('2'), 0xA4 => P('3'), 0xA5 => P('4'),
     0xA6 => P('5'), 0xA7 => P('6'), 0xA8 => P('7'), 0xA9 => P('8'), 0xAA => P('9'),
     0xAB => P('!'), 0xAC => P('?'), 0xAD => P('.'), 0xAE => P('-'),
+    0xB4 => P('\''), 0xB5 => P('♂'), 0xB6 => P('♀'),
     0xBB => P('A'), 0xBC => P('B'), 0xBD => P('C'), 0xBE => P('D'), 0xBF => P('E'),
     0xC0 => P('F'), 0xC1 => P('G'), 0xC2 => P('H'), 0xC3 => P('I'), 0xC4 => P('J'),
     0xC5 => P('K'), 0xC6 => P('L'), 0xC7 => P('M'), 0xC8 => P('N'), 0xC9 => P('O'),
@@ -63,23 +64,77 @@ map! {
     0xFF => PokeChar::Term,
 }
 
+/// The character set a string is encoded with, which depends on the language of the game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Charset {
+    /// Used by the English, French, Italian, German and Spanish games.
+    Western,
+    Japanese,
+}
+
+/// The Japanese characters, in encoding order from 0x00. Full-width characters are used where
+/// the western set uses ASCII ones.
+const JAPANESE_CHARS: &str = concat!(
+    "　",
+    "あいうえおかきくけこさしすせそたちつてとなにぬねのはひふへほまみむめもやゆよらりるれろわをん",
+    "ぁぃぅぇぉゃゅょがぎぐげござじずぜぞだぢづでどばびぶべぼぱぴぷぺぽっ",
+    "アイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワヲン",
+    "ァィゥェォャュョガギグゲゴザジズゼゾダヂヅデドバビブベボパピプペポッ",
+    "0123456789！？。ー・‥『』「」♂♀円．×／",
+    "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz",
+);
+
+thread_local! {
+    static ENCODED_TO_JAPANESE: HashMap<u8, PokeChar> = JAPANESE_CHARS
+        .chars()
+        .enumerate()
+        .map(|(i, ch)| (i as u8, P(ch)))
+        .chain(std::iter::once((0xFF, PokeChar::Term)))
+        .collect();
+    static JAPANESE_TO_ENCODED: HashMap<PokeChar, u8> = ENCODED_TO_JAPANESE
+        .with(|map| map.iter().map(|(&nv, &pc)| (pc, nv)).collect());
+}
+
 pub fn decode_byte(value: u8) -> PokeChar {
     ENCODED_TO_POKECHAR.with(|map| map.get(&value).cloned().unwrap_or(PokeChar::Unmapped))
 }
 
+/// Decode `value` as a character of `charset`.
+pub fn decode_byte_with(value: u8, charset: Charset) -> PokeChar {
+    match charset {
+        Charset::Western => decode_byte(value),
+        Charset::Japanese => {
+            ENCODED_TO_JAPANESE.with(|map| map.get(&value).cloned().unwrap_or(PokeChar::Unmapped))
+        }
+    }
+}
+
 pub fn decode_string(poke: &[u8]) -> String {
+    decode_string_with(poke, Charset::Western)
+}
+
+/// Decode `poke` as a string of `charset`.
+pub fn decode_string_with(poke: &[u8], charset: Charset) -> String {
     poke.iter()
-        .cloned()
-        .map(decode_byte)
+        .map(|&b| decode_byte_with(b, charset))
         .take_while(|&pc| pc != PokeChar::Term)
         .map(PokeChar::to_char)
         .collect()
 }
 
 pub fn encode_string(src: &str, dst: &mut [u8]) {
+    encode_string_with(src, dst, Charset::Western)
+}
+
+/// Encode `src` as a string of `charset` into `dst`.
+pub fn encode_string_with(src: &str, dst: &mut [u8], charset: Charset) {
     let mut dst_bytes = dst.iter_mut();
     for ch in src.chars().map(|ch| {
-        POKECHAR_TO_ENCODED.with(|map| map.get(&PokeChar::Print(ch)).cloned().expect("No mapping"))
+        let map = match charset {
+            Charset::Western => &POKECHAR_TO_ENCODED,
+            Charset::Japanese => &JAPANESE_TO_ENCODED,
+        };
+        map.with(|map| map.get(&PokeChar::Print(ch)).cloned().expect("No mapping"))
     }) {
         *dst_bytes.next().unwrap() = ch;
     }
@@ -94,4 +149,8 @@ fn test_decode_string() {
         decode_string(&[0xC2, 0xD9, 0xE0, 0xE0, 0xE3, 0xFF]),
         "Hello"
     );
+    assert_eq!(
+        decode_string_with(&[0x60, 0x6F, 0x8B, 0xFF], Charset::Japanese),
+        "タマゴ"
+    );
 }